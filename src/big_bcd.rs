@@ -1,334 +1,433 @@
-// Very simple big_bcd. Each digit is a u8, so space is not so efficient.
-// Also, the algorithms used are very simple, and are far from optimal.
+// Big decimal integers. The magnitude is stored in base 10^9 limbs, nine
+// decimal digits to a u32, least significant limb first, so the value keeps
+// its decimal semantics while the algorithms work on whole limbs.
+//
+// Values are stored in sign-magnitude form: `limbs` holds the magnitude and
+// `neg` the sign. Every value is kept in a canonical form, see `is_canonical`,
+// so that it has exactly one representation.
 
-use std::convert::From;
+use std::convert::{From, TryFrom};
 use std::fmt;
-use itertools::{
-    Itertools,
-    EitherOrBoth::*,
-};
-use std::cmp::{max, Eq, PartialEq, Ord, PartialOrd, Ordering};
+use std::cmp::{Eq, PartialEq, Ord, PartialOrd, Ordering};
+use std::error::Error;
+use std::iter::{Product, Sum};
+use std::str::FromStr;
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+mod bits;
+mod comb;
+pub mod constants;
+mod convert;
+mod decimal;
+mod digits;
+mod div;
+mod format;
+mod ieee;
+mod mod_context;
+mod modular;
+mod mul;
+mod ntt;
+mod num;
+mod ops;
+mod packed;
+mod parallel;
+mod radix;
+mod random;
+mod root;
+#[cfg(feature = "serde")]
+mod serialize;
+
+pub use convert::TryFromBigBcdError;
+pub use decimal::{BigDecimal, DecimalError, RoundingMode};
+pub use digits::{DigitPermutations, Digits};
+pub use format::Grouping;
+pub use ieee::{DecimalEncoding, DecimalFormat, IeeeDecimal};
+pub use mod_context::{ConstTimeModContext, ModContext};
+pub use packed::{BcdError, ByteOrder};
+#[cfg(feature = "parallel")]
+pub use parallel::{set_threads, threads};
+pub use random::{RandomSource, XorShiftRng};
+pub use root::RootError;
+
+const BASE: u32 = 1_000_000_000;
+const LIMB_DIGITS: usize = 9;
+
+// Every value has exactly one representation, so the derived Eq and Hash
+// compare values.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct BigBcd {
-    digs: Vec<u8>,
+    limbs: Vec<u32>,
+    neg: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    // No digits, after trimming whitespace and the sign.
+    Empty,
+    // `position` is the byte offset of `found` in the original input.
+    InvalidDigit { position: usize, found: char },
+    InvalidRadix(u32),
+    // A BigDecimal exponent or scale that does not fit in an i64.
+    ExponentOutOfRange,
 }
 
-impl From<String> for BigBcd {
-    fn from(s: String) -> Self {
-        let mut rv = vec![];
-        rv.reserve(s.len());
-        for c in s.chars().rev() {
-            rv.push(c.to_digit(10).unwrap() as u8);
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "cannot parse BigBcd from empty string"),
+            ParseError::InvalidDigit { position, found } => {
+                write!(f, "invalid digit {:?} at position {} in BigBcd", found, position)
+            }
+            ParseError::InvalidRadix(r) => write!(f, "radix {} is not in 2..=36", r),
+            ParseError::ExponentOutOfRange => write!(f, "exponent out of range"),
         }
-        BigBcd { digs: rv }
     }
 }
 
-impl From<&str> for BigBcd {
-    fn from(s: &str) -> Self {
-        BigBcd::from(String::from(s))
+impl Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DivisionByZero;
+
+impl fmt::Display for DivisionByZero {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "attempt to divide BigBcd by zero")
     }
 }
 
-impl From<usize> for BigBcd {
-    fn from(mut x: usize) -> Self {
-        let mut rv = vec![];
-        while x > 0 {
-            rv.push((x % 10) as u8);
-            x /= 10;
-        }
-        if rv.len() == 0 {
-            rv.push(0);
-        }
-        BigBcd { digs: rv }
+impl Error for DivisionByZero {}
+
+impl FromStr for BigBcd {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let (neg, digits) = parse_digits(s, 10)?;
+        Ok(BigBcd::from_digits_be(&digits, neg))
     }
 }
 
-impl fmt::Display for BigBcd {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.digs
-                .iter()
-                .rev()
-                .map(|x| (x + '0' as u8) as char)
-                .collect::<String>()
-        )
+impl TryFrom<&str> for BigBcd {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, ParseError> {
+        s.parse()
     }
 }
 
 impl BigBcd {
-    pub fn add(&self, other: &Self) -> Self {
-        let mut res_vec: Vec<u8> = vec![];
-        res_vec.reserve(max(self.digs.len(), other.digs.len()));
-
-        let mut i = 0;
-        let mut carry: u8 = 0;
-
-        for pair in self.digs.iter().zip_longest(other.digs.iter()) {
-            match pair {
-                Both(x, y) => {
-                    let mut curr: u8 = x + y + carry;
-                    carry = curr / 10;
-                    curr %= 10;
-                    res_vec.push(curr);
-                }
-                Left(x) => {
-                    let mut curr: u8 = x + carry;
-                    carry = curr / 10;
-                    curr %= 10;
-                    res_vec.push(curr);
-                }
-                Right(y) => {
-                    let mut curr: u8 = y + carry;
-                    carry = curr / 10;
-                    curr %= 10;
-                    res_vec.push(curr);
-                }
-            };
+    // Builds (-1)^neg * x.
+    fn from_mag(mut x: u128, neg: bool) -> Self {
+        let mut limbs = vec![];
+        while x > 0 {
+            limbs.push((x % BASE as u128) as u32);
+            x /= BASE as u128;
         }
+        let mut res = BigBcd { limbs, neg };
+        res.trim();
+        res
+    }
 
-        if carry > 0 {
-            res_vec.push(carry);
+    // Builds (-1)^neg * the number with the given decimal digits, most
+    // significant first.
+    fn from_digits_be(digits: &[u8], neg: bool) -> Self {
+        let limbs = digits
+            .rchunks(LIMB_DIGITS)
+            .map(|chunk| chunk.iter().fold(0, |acc, &d| acc * 10 + d as u32))
+            .collect();
+        let mut res = BigBcd { limbs, neg };
+        res.trim();
+        res
+    }
+
+    // 10^k
+    fn pow10(k: usize) -> Self {
+        let mut limbs = vec![0; k / LIMB_DIGITS];
+        limbs.push(10u32.pow((k % LIMB_DIGITS) as u32));
+        BigBcd { limbs, neg: false }
+    }
+
+    // The decimal digits of |self|, most significant first.
+    fn to_digits_be(&self) -> Vec<u8> {
+        let mut digits = Vec::with_capacity(self.limbs.len() * LIMB_DIGITS);
+        for &limb in self.limbs.iter().rev() {
+            let start = digits.len();
+            digits.resize(start + LIMB_DIGITS, 0);
+            let mut limb = limb;
+            for d in digits[start..].iter_mut().rev() {
+                *d = (limb % 10) as u8;
+                limb /= 10;
+            }
         }
+        let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..leading_zeros.min(digits.len() - 1));
+        digits
+    }
 
-        BigBcd { digs: res_vec }
+    pub fn is_zero(&self) -> bool {
+        self.limbs.len() == 1 && self.limbs[0] == 0
     }
 
-    pub fn subtract(&self, other: &Self) -> Self {
-        let mut res_vec: Vec<u8> = vec![];
-        res_vec.reserve(self.digs.len());
-
-        let mut carry: u8 = 0;
-
-        for pair in self.digs.iter().zip_longest(other.digs.iter()) {
-            match pair {
-                Both(x, y) => {
-                    let mut curr: i8 = (*x as i8) - (carry as i8) - (*y as i8);
-                    carry = 0;
-                    if curr < 0 {
-                        carry = 1;
-                        curr += 10;
-                    }
-                    res_vec.push(curr as u8);
-                }
-                Left(x) => {
-                    let mut curr: i8 = (*x as i8) - (carry as i8);
-                    carry = 0;
-                    if curr < 0 {
-                        carry = 1;
-                        curr += 10;
-                    }
-                    res_vec.push(curr as u8);
-                }
-                Right(y) => {
-                    // underflow
-                    return BigBcd { digs: vec![0] };
-                }
-            };
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn is_positive(&self) -> bool {
+        !self.neg && !self.is_zero()
+    }
+
+    pub fn abs(&self) -> Self {
+        BigBcd {
+            limbs: self.limbs.clone(),
+            neg: false,
         }
+    }
 
-        for i in (1..res_vec.len()).rev() {
-            if res_vec[i] == 0 {
-                res_vec.pop();
-            } else {
-                break;
+    pub fn negated(&self) -> Self {
+        let mut res = self.clone();
+        res.neg = !res.neg;
+        res.fix_sign();
+        res
+    }
+
+    // The invariant every BigBcd satisfies between operations: at least one
+    // limb, every limb below BASE, no leading zero limbs, and zero stored as
+    // a single 0 limb that is not negative. The derived Eq and Hash, and
+    // cmp_mag, rely on it.
+    pub(crate) fn is_canonical(&self) -> bool {
+        match self.limbs.last() {
+            None => false,
+            Some(&top) => {
+                self.limbs.iter().all(|&limb| limb < BASE)
+                    && (top != 0 || (self.limbs.len() == 1 && !self.neg))
             }
         }
+    }
 
-        BigBcd { digs: res_vec }
+    // Checks the invariant in debug builds, where a value leaves a
+    // constructor or an operation.
+    fn debug_check(&self) {
+        debug_assert!(self.is_canonical(), "non-canonical BigBcd: {:?}", self);
     }
 
-    fn dig_mul(&mut self, large: &Self, small: &Self, place: usize) {
-        let dig: u8 = small.digs[place];
-        if dig == 0 {
-            return;
+    // Zero must never be negative, otherwise the derived Eq breaks.
+    fn fix_sign(&mut self) {
+        if self.is_zero() {
+            self.neg = false;
         }
-        
-        let mut i: usize = 0;
-        let mut carry: u8 = 0;
-        while i < large.digs.len() {
-            let mut curr: u8 = large.digs[i] * dig + carry;
-            if i + place < self.digs.len() {
-                curr += self.digs[i + place];
-            } else {
-                for _ in self.digs.len()..=(i + place) {
-                    self.digs.push(0);
-                }
-            }
-            carry = curr / 10;
-            curr %= 10;
-            self.digs[i + place] = curr;
-            i += 1;
+    }
+
+    // Removes leading zeros, keeping a single 0 for zero.
+    fn trim(&mut self) {
+        while self.limbs.len() > 1 && *self.limbs.last().unwrap() == 0 {
+            self.limbs.pop();
         }
-        while carry > 0 {
-            let mut curr = carry;
-            if i + place < self.digs.len() {
-                curr += self.digs[i + place];
-            } else {
-                for _ in self.digs.len()..(i + place) {
-                    self.digs.push(0);
-                }
+        if self.limbs.is_empty() {
+            self.limbs.push(0);
+        }
+        self.fix_sign();
+        self.debug_check();
+    }
+
+    fn cmp_mag(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+
+    // |self| += |other|, in place.
+    fn add_mag_assign(&mut self, other: &Self) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for i in 0..self.limbs.len() {
+            if i >= other.limbs.len() && carry == 0 {
+                break;
             }
-            carry = curr / 10;
-            curr %= 10;
-            self.digs[i + place] = curr;
-            i += 1;
+            let mut curr = self.limbs[i] + carry;
+            if i < other.limbs.len() {
+                curr += other.limbs[i];
+            }
+            carry = (curr >= BASE) as u32;
+            self.limbs[i] = curr - carry * BASE;
+        }
+
+        if carry > 0 {
+            self.limbs.push(carry);
         }
     }
 
-    pub fn multiply(&self, other: &Self) -> Self {
-        let small;
-        let large;
-        if self.digs.len() > other.digs.len() {
-            large = self;
-            small = other;
-        } else {
-            large = other;
-            small = self;
+    // |self| = ||self| - |other||, in place. Returns true if |other| was the
+    // larger, i.e. the difference changed sign.
+    fn sub_mag_assign(&mut self, other: &Self) -> bool {
+        let flip = self.cmp_mag(other) == Ordering::Less;
+        if flip {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut borrow = 0;
+        for i in 0..self.limbs.len() {
+            let y = if i < other.limbs.len() {
+                other.limbs[i] as i64
+            } else if borrow == 0 {
+                break;
+            } else {
+                0
+            };
+            let x = self.limbs[i] as i64;
+            let mut curr = if flip { y - x - borrow } else { x - y - borrow };
+            borrow = (curr < 0) as i64;
+            curr += borrow * BASE as i64;
+            self.limbs[i] = curr as u32;
         }
 
-        let mut res = BigBcd { digs: vec![0] };
+        self.trim();
+        flip
+    }
 
-        for place in 0..small.digs.len() {
-            res.dig_mul(large, small, place);
+    // self += (-1)^other_neg * |other|, in place.
+    fn add_signed_assign(&mut self, other: &Self, other_neg: bool) {
+        if self.neg == other_neg {
+            self.add_mag_assign(other);
+        } else if self.sub_mag_assign(other) {
+            self.neg = other_neg;
         }
+        self.fix_sign();
+        self.debug_check();
+    }
 
+    pub fn add(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        res.add_signed_assign(other, other.neg);
+        res
+    }
+
+    pub fn subtract(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        res.add_signed_assign(other, !other.neg);
         res
     }
 
-    fn sub_pow_inplace(&mut self, other: &Self, pow: usize) -> bool {
-        let mut borrow: i8 = 0;
-        let mut i: usize = 0;
-        while i < other.digs.len() {
-            let mut curr: i8 = (self.digs[i + pow] as i8) - (other.digs[i] as i8)  
-                               - borrow;
-            borrow = (curr < 0) as i8;
-            curr += borrow * 10;
-            self.digs[i + pow] = curr as u8;
-            i += 1;
+    // self *= m for a machine-word m, in place.
+    fn mul_small_assign(&mut self, m: u64) {
+        if m == 0 {
+            self.limbs.truncate(1);
+            self.limbs[0] = 0;
+            self.neg = false;
+            return;
         }
-        i += pow;
-
-        while borrow > 0 && i < self.digs.len() {
-            let mut curr: i8 = (self.digs[i + pow] as i8) - borrow;
-            borrow = (curr < 0) as i8;
-            curr += borrow * 10;
-            self.digs[i + pow] = curr as u8;
-            i += 1;
+
+        let mut carry: u128 = 0;
+        for limb in self.limbs.iter_mut() {
+            let curr = (*limb as u128) * (m as u128) + carry;
+            *limb = (curr % BASE as u128) as u32;
+            carry = curr / BASE as u128;
         }
-        for i in (1..self.digs.len()).rev() {
-            if self.digs[i] == 0 {
-                self.digs.pop();
-            } else {
-                break;
-            }
+        while carry > 0 {
+            self.limbs.push((carry % BASE as u128) as u32);
+            carry /= BASE as u128;
         }
-        borrow > 0
-    }
-
-    fn greater_eq_pow(&self, other: &Self, pow: usize) -> bool {
-        let diff = (self.digs.len() as isize) - (other.digs.len() as isize) - (pow as isize);
-        if diff < 0 {
-            false
-        } else if diff > 0 {
-            true
-        } else {
-            for i in (0..other.digs.len()).rev() {
-                let d = (self.digs[i + pow] as i8) - (other.digs[i] as i8);
-                if d > 0 {
-                    return true;
-                } else if d < 0 {
-                    return false;
-                }
-            }
+        self.debug_check();
+    }
 
-            true
+    // |self| /= d for a machine-word d, in place, returning |self| % d.
+    fn div_rem_small_assign(&mut self, d: u64) -> u64 {
+        let mut rem: u128 = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            rem = rem * BASE as u128 + (*limb as u128);
+            *limb = (rem / (d as u128)) as u32;
+            rem %= d as u128;
         }
+        self.trim();
+        rem as u64
     }
 
-    pub fn divide(&self, other: &Self) -> Self {
-        let mut res = BigBcd::from(0);
-        if self.digs.len() >= other.digs.len() {
-            res.digs = vec![0; self.digs.len()];
-            let mut n = self.clone();
-            for pow in (0..=(self.digs.len() - other.digs.len())).rev() {
-                res.digs[pow] = 0;
-                while n.greater_eq_pow(other, pow) {
-                    n.sub_pow_inplace(other, pow);
-                    res.digs[pow] += 1;
-                }
-            }
-            for i in (1..(res.digs.len() - 1)).rev() {
-                if res.digs[i] == 0 {
-                    res.digs.pop();
-                } else {
-                    break;
-                }
-            }
-        }
+    // Picks schoolbook, Karatsuba, Toom-3 or NTT multiplication by operand
+    // size.
+    pub fn multiply(&self, other: &Self) -> Self {
+        self.multiply_with(other, mul::mul_limbs)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn multiply_schoolbook(&self, other: &Self) -> Self {
+        self.multiply_with(other, mul::schoolbook)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn multiply_karatsuba(&self, other: &Self) -> Self {
+        self.multiply_with(other, mul::karatsuba)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn multiply_toom3(&self, other: &Self) -> Self {
+        self.multiply_with(other, mul::toom3)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn multiply_ntt(&self, other: &Self) -> Self {
+        self.multiply_with(other, ntt::mul)
+    }
 
+    fn multiply_with(&self, other: &Self, f: fn(&[u32], &[u32]) -> Vec<u32>) -> Self {
+        let mut res = BigBcd {
+            limbs: f(&self.limbs, &other.limbs),
+            neg: self.neg != other.neg,
+        };
+        res.trim();
         res
     }
 
-    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
-        let mut res = BigBcd::from(0);
-        if self.digs.len() >= other.digs.len() {
-            res.digs = vec![0; self.digs.len()];
-            let mut n = self.clone();
-            for pow in (0..=(self.digs.len() - other.digs.len())).rev() {
-                res.digs[pow] = 0;
-                while n.greater_eq_pow(other, pow) {
-                    n.sub_pow_inplace(other, pow);
-                    res.digs[pow] += 1;
-                }
-            }
-            for i in (1..res.digs.len()).rev() {
-                if res.digs[i] == 0 {
-                    res.digs.pop();
-                } else {
-                    break;
-                }
-            }
-            (res, n)
-        } else {
-            (res, self.clone())
+    // Truncated division: the quotient is rounded towards zero and the
+    // remainder takes the sign of the dividend (like the primitive `/` and `%`).
+    pub fn div_rem(&self, other: &Self) -> Result<(Self, Self), DivisionByZero> {
+        if other.is_zero() {
+            return Err(DivisionByZero);
         }
+        let (mut q, mut r) = div::div_rem_mag(self, other);
+        q.neg = self.neg != other.neg;
+        r.neg = self.neg;
+        q.fix_sign();
+        r.fix_sign();
+        q.debug_check();
+        r.debug_check();
+        Ok((q, r))
     }
 
-    pub fn rem(&self, other: &Self) -> Self {
-        if self.digs.len() >= other.digs.len() {
-            let mut n = self.clone();
-            for pow in (0..=(self.digs.len() - other.digs.len())).rev() {
-                while n.greater_eq_pow(other, pow) {
-                    n.sub_pow_inplace(other, pow);
-                }
-            }
-            n
-        } else {
-            self.clone()
+    pub fn divide(&self, other: &Self) -> Result<Self, DivisionByZero> {
+        self.div_rem(other).map(|(q, _)| q)
+    }
+
+    pub fn rem(&self, other: &Self) -> Result<Self, DivisionByZero> {
+        self.div_rem(other).map(|(_, r)| r)
+    }
+
+    // Euclidean division: the remainder is always non-negative, so that
+    // self == q * other + r with 0 <= r < |other|.
+    pub fn div_rem_euclid(&self, other: &Self) -> Result<(Self, Self), DivisionByZero> {
+        let (mut q, mut r) = self.div_rem(other)?;
+        if r.neg {
+            r.add_signed_assign(other, false);
+            q.add_signed_assign(&BigBcd::from(1), !other.neg);
         }
+        Ok((q, r))
+    }
+
+    pub fn div_euclid(&self, other: &Self) -> Result<Self, DivisionByZero> {
+        self.div_rem_euclid(other).map(|(q, _)| q)
+    }
+
+    pub fn rem_euclid(&self, other: &Self) -> Result<Self, DivisionByZero> {
+        self.div_rem_euclid(other).map(|(_, r)| r)
     }
 }
 
 impl Ord for BigBcd {
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.digs.len() > other.digs.len() {
-            Ordering::Greater
-        } else if self.digs.len() == other.digs.len() {
-            for (x, y) in self.digs.iter().zip(other.digs.iter()).rev() {
-                let d = (*x as i8) - (*y as i8);
-                if d > 0 {
-                    return Ordering::Greater;
-                } else if d < 0 {
-                    return Ordering::Less;
-                }
-            }
-            Ordering::Equal
-        } else {
-            Ordering::Less
+        self.debug_check();
+        other.debug_check();
+        match (self.neg, other.neg) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_mag(other),
+            (true, true) => other.cmp_mag(self),
         }
     }
 }
@@ -338,3 +437,74 @@ impl PartialOrd for BigBcd {
         Some(self.cmp(other))
     }
 }
+
+impl Default for BigBcd {
+    fn default() -> Self {
+        BigBcd::from(0)
+    }
+}
+
+impl Sum for BigBcd {
+    fn sum<I: Iterator<Item = BigBcd>>(iter: I) -> Self {
+        iter.fold(BigBcd::from(0), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BigBcd> for BigBcd {
+    fn sum<I: Iterator<Item = &'a BigBcd>>(iter: I) -> Self {
+        iter.fold(BigBcd::from(0), |acc, x| acc + x)
+    }
+}
+
+impl Product for BigBcd {
+    fn product<I: Iterator<Item = BigBcd>>(iter: I) -> Self {
+        iter.fold(BigBcd::from(1), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BigBcd> for BigBcd {
+    fn product<I: Iterator<Item = &'a BigBcd>>(iter: I) -> Self {
+        iter.fold(BigBcd::from(1), |acc, x| acc * x)
+    }
+}
+
+// Splits s into its sign and digit values, most significant first. Surrounding
+// whitespace and a leading '+' or '-' are accepted, as are '_' separators
+// between digits.
+fn parse_digits(s: &str, radix: u32) -> Result<(bool, Vec<u8>), ParseError> {
+    let start = s.len() - s.trim_start().len();
+    let body = s.trim();
+    let (neg, body, start) = match body.as_bytes().first() {
+        Some(b'-') => (true, &body[1..], start + 1),
+        Some(b'+') => (false, &body[1..], start + 1),
+        _ => (false, body, start),
+    };
+    if body.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut digits = Vec::with_capacity(body.len());
+    let mut after_digit = false;
+    for (i, c) in body.char_indices() {
+        let invalid = ParseError::InvalidDigit {
+            position: start + i,
+            found: c,
+        };
+        // A separator must sit between two digits, so "1__2" is rejected.
+        if c == '_' {
+            if !after_digit || i + 1 == body.len() {
+                return Err(invalid);
+            }
+            after_digit = false;
+            continue;
+        }
+        let d = c.to_digit(radix).ok_or(invalid)?;
+        digits.push(d as u8);
+        after_digit = true;
+    }
+    Ok((neg, digits))
+}
+
+fn cmp_limbs(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
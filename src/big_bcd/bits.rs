@@ -0,0 +1,147 @@
+// Bit-level operations, with the infinite-precision two's-complement
+// semantics of Python's ints: a negative x behaves as if it had infinitely
+// many leading ones, so -1 is all ones and x & -x isolates the lowest set
+// bit. The digits are decimal, so every operation here goes through a binary
+// conversion first.
+
+use super::radix::{from_chunks, to_chunks};
+use super::BigBcd;
+
+// Binary words are carried in u64s but hold 32 bits each.
+const WORD: u64 = 1 << 32;
+
+// The two's-complement words of x, least significant first, and whether the
+// infinite extension past them is all ones.
+fn to_twos(x: &BigBcd) -> (Vec<u32>, bool) {
+    if !x.neg {
+        let words = to_chunks(x, WORD).iter().map(|&w| w as u32).collect();
+        return (words, false);
+    }
+    // -m is the complement of m - 1.
+    let mut m = x.abs();
+    m.sub_mag_assign(&BigBcd::from(1));
+    let words = to_chunks(&m, WORD).iter().map(|&w| !(w as u32)).collect();
+    (words, true)
+}
+
+fn from_twos(words: &[u32], neg: bool) -> BigBcd {
+    if !neg {
+        let words: Vec<u64> = words.iter().map(|&w| w as u64).collect();
+        return from_chunks(&words, WORD);
+    }
+    let words: Vec<u64> = words.iter().map(|&w| !w as u64).collect();
+    let mut res = from_chunks(&words, WORD);
+    res.add_mag_assign(&BigBcd::from(1));
+    res.neg = true;
+    res
+}
+
+// 2^k, by squaring over all the bits of k, since `pow` takes a u32.
+fn pow2(mut k: usize) -> BigBcd {
+    let mut res = BigBcd::from(1);
+    let mut base = BigBcd::from(2);
+    while k > 0 {
+        if k & 1 == 1 {
+            res *= &base;
+        }
+        k >>= 1;
+        if k > 0 {
+            base = base.multiply(&base);
+        }
+    }
+    res
+}
+
+impl BigBcd {
+    // Applies op to each pair of two's-complement words, including the
+    // infinite extensions.
+    pub(super) fn bitwise(&self, other: &Self, op: impl Fn(u32, u32) -> u32) -> Self {
+        let (mut a, a_neg) = to_twos(self);
+        let (mut b, b_neg) = to_twos(other);
+        let ext = |neg: bool| if neg { u32::MAX } else { 0 };
+        let len = a.len().max(b.len());
+        a.resize(len, ext(a_neg));
+        b.resize(len, ext(b_neg));
+        let words: Vec<u32> = a.iter().zip(b.iter()).map(|(&x, &y)| op(x, y)).collect();
+        from_twos(&words, op(ext(a_neg), ext(b_neg)) != 0)
+    }
+
+    // self * 2^k
+    pub fn shl2(&self, k: usize) -> Self {
+        if k < 64 {
+            let mut res = self.clone();
+            res.mul_small_assign(1 << k);
+            res.fix_sign();
+            return res;
+        }
+        if self.is_zero() {
+            return BigBcd::from(0);
+        }
+        self.multiply(&pow2(k))
+    }
+
+    // self / 2^k rounded towards negative infinity, which is an arithmetic
+    // shift in two's complement.
+    pub fn shr2(&self, k: usize) -> Self {
+        if k < 64 {
+            let mut res = self.clone();
+            if res.div_rem_small_assign(1 << k) != 0 && self.neg {
+                res.neg = true;
+                res.add_mag_assign(&BigBcd::from(1));
+            }
+            return res;
+        }
+        // Every limb is below 2^30, so past 30 bits a limb the shift leaves
+        // only the sign.
+        if k / 30 >= self.limbs.len() {
+            return BigBcd::from(if self.neg { -1 } else { 0 });
+        }
+        self.div_euclid(&pow2(k)).unwrap()
+    }
+
+    // The number of bits in |self|, 0 for zero.
+    pub fn bit_length(&self) -> u64 {
+        let words = to_chunks(self, WORD);
+        match words.last() {
+            Some(&top) => words.len() as u64 * 32 - (top as u32).leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    // The number of ones in |self|. A negative number has infinitely many in
+    // two's complement, so this counts those of its magnitude instead.
+    pub fn count_ones(&self) -> u64 {
+        to_chunks(self, WORD)
+            .iter()
+            .map(|&w| w.count_ones() as u64)
+            .sum()
+    }
+
+    // The number of trailing zero bits, which is the same for self and
+    // -self. None for zero.
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        let words = to_chunks(self, WORD);
+        let i = words.iter().position(|&w| w != 0)?;
+        Some(i as u64 * 32 + words[i].trailing_zeros() as u64)
+    }
+
+    // Bit i of the two's complement, i.e. of floor(self / 2^i).
+    pub fn test_bit(&self, i: usize) -> bool {
+        // BASE is even, so the lowest limb has the parity of the number.
+        self.shr2(i).limbs[0] & 1 == 1
+    }
+
+    // Sets bit i of the two's complement to `value`, by adding or
+    // subtracting 2^i if it differs.
+    pub fn set_bit(&mut self, i: usize, value: bool) {
+        if self.test_bit(i) == value {
+            return;
+        }
+        let bit = BigBcd::from(1).shl2(i);
+        if value {
+            *self += &bit;
+        } else {
+            *self -= &bit;
+        }
+    }
+}
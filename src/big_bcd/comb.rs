@@ -0,0 +1,134 @@
+// Factorials, binomial and multinomial coefficients, Catalan numbers, and
+// Fibonacci and Lucas numbers.
+//
+// Long products are formed as balanced product trees, so that the big
+// multiplications pair operands of similar size where the fast algorithms
+// pay off, instead of growing one product by a word at a time.
+
+use super::{parallel, BigBcd};
+use crate::primes::sieve;
+
+// Factors are packed into words below this before going into a product tree,
+// so the leaves can use the one-limb multiplication.
+const WORD_LIMIT: u64 = 1 << 32;
+
+// Leaves of a product tree at most this long are multiplied out in a loop.
+const LEAF: usize = 16;
+
+// The product of the words, as a balanced tree.
+fn product(words: &[u64]) -> BigBcd {
+    if words.len() <= LEAF {
+        let mut res = BigBcd::from(1);
+        for &w in words {
+            res.mul_small_assign(w);
+        }
+        return res;
+    }
+    let (lo, hi) = words.split_at(words.len() / 2);
+    let (lo, hi) = parallel::join(words.len(), || product(lo), || product(hi));
+    lo.multiply(&hi)
+}
+
+// Packs the factors into as few words below WORD_LIMIT as possible, greedily.
+fn pack(factors: impl Iterator<Item = u64>) -> Vec<u64> {
+    let mut words = vec![];
+    let mut curr = 1;
+    for f in factors {
+        if curr * f >= WORD_LIMIT {
+            words.push(curr);
+            curr = 1;
+        }
+        curr *= f;
+    }
+    words.push(curr);
+    words
+}
+
+// The exponent of the prime p in n!, by Legendre's formula.
+fn legendre(mut n: usize, p: usize) -> usize {
+    let mut e = 0;
+    while n > 0 {
+        n /= p;
+        e += n;
+    }
+    e
+}
+
+// The product over primes p <= n of p^exp(p).
+fn prime_product(n: usize, exp: impl Fn(usize) -> usize) -> BigBcd {
+    if n < 2 {
+        return BigBcd::from(1);
+    }
+    let primes = sieve(n);
+    let factors = primes
+        .iter()
+        .flat_map(|&p| std::iter::repeat_n(p as u64, exp(p)));
+    product(&pack(factors))
+}
+
+impl BigBcd {
+    // n!, by binary splitting of 1 * 2 * ... * n.
+    pub fn factorial(n: usize) -> Self {
+        product(&pack(2..=n as u64))
+    }
+
+    // n choose k, from its prime factorization. Zero if k > n.
+    pub fn binomial(n: usize, k: usize) -> Self {
+        if k > n {
+            return BigBcd::from(0);
+        }
+        prime_product(n, |p| legendre(n, p) - legendre(k, p) - legendre(n - k, p))
+    }
+
+    // (k_1 + ... + k_m)! / (k_1! * ... * k_m!), from its prime
+    // factorization.
+    pub fn multinomial(ks: &[usize]) -> Self {
+        let n = ks.iter().sum();
+        prime_product(n, |p| {
+            legendre(n, p) - ks.iter().map(|&k| legendre(k, p)).sum::<usize>()
+        })
+    }
+
+    // The n-th Catalan number, C(2n, n) / (n + 1).
+    pub fn catalan(n: usize) -> Self {
+        let mut res = BigBcd::binomial(2 * n, n);
+        res.div_rem_small_assign(n as u64 + 1);
+        res
+    }
+
+    // The n-th Fibonacci number, with F(0) = 0 and F(1) = 1.
+    pub fn fibonacci(n: u64) -> Self {
+        fibonacci_pair(n).0
+    }
+
+    // The n-th Lucas number, with L(0) = 2 and L(1) = 1.
+    pub fn lucas(n: u64) -> Self {
+        // L(n) = F(n - 1) + F(n + 1) = 2 F(n + 1) - F(n)
+        let (f, mut g) = fibonacci_pair(n);
+        g.mul_small_assign(2);
+        g.subtract(&f)
+    }
+}
+
+// (F(n), F(n + 1)) by fast doubling, from the top bit of n down:
+// F(2k) = F(k) (2 F(k + 1) - F(k)) and F(2k + 1) = F(k)^2 + F(k + 1)^2.
+fn fibonacci_pair(n: u64) -> (BigBcd, BigBcd) {
+    let (mut a, mut b) = (BigBcd::from(0), BigBcd::from(1));
+    for i in (0..64 - n.leading_zeros()).rev() {
+        let mut twice_b = b.clone();
+        twice_b.mul_small_assign(2);
+        let (c, d) = parallel::join(
+            b.limbs.len(),
+            || a.multiply(&twice_b.subtract(&a)),
+            || a.multiply(&a).add(&b.multiply(&b)),
+        );
+        if (n >> i) & 1 == 1 {
+            b = c.add(&d);
+            a = d;
+        } else {
+            a = c;
+            b = d;
+        }
+    }
+    (a, b)
+}
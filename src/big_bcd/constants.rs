@@ -0,0 +1,152 @@
+// Mathematical constants to n decimal places. Each function returns the
+// constant times 10^n, truncated, so pi(5) is 314159.
+//
+// Series are summed by binary splitting, which keeps everything in exact
+// integers until one final division, and lets the fast multiplication do
+// most of the work. The square roots come straight from `isqrt`.
+
+use super::{parallel, BigBcd};
+
+// The series below come within this many units in the last place of the
+// true value, counting the tail of the series and the truncating divisions.
+const ERR: u64 = 100;
+
+// x * 10^n truncated, given approx(p) within ERR of x * 10^p. A few guard
+// digits are almost always enough, but if the approximation is too close to
+// a multiple of 10^guard to be sure which way it falls, retry with more.
+fn truncated(n: usize, approx: impl Fn(usize) -> BigBcd) -> BigBcd {
+    let mut guard = 10;
+    loop {
+        let x = approx(n + guard);
+        let res = x.shr10(guard);
+        let rem = x.subtract(&res.shl10(guard));
+        let err = BigBcd::from(ERR);
+        if rem >= err && rem <= BigBcd::pow10(guard).subtract(&err) {
+            return res;
+        }
+        guard *= 2;
+    }
+}
+
+// The partial sum of a hypergeometric-like series over k in [lo, hi):
+//   sum a(k) / b(k) * (p(lo) ... p(k)) / (q(lo) ... q(k))
+// as (P, Q, B, T) with the sum equal to T / (B Q), where P and Q are the
+// products of p and q over the range and B the product of b.
+struct Split {
+    p: BigBcd,
+    q: BigBcd,
+    b: BigBcd,
+    t: BigBcd,
+}
+
+// `term(k)` gives (a(k), b(k), p(k), q(k)).
+fn split(lo: u64, hi: u64, term: &(impl Fn(u64) -> [BigBcd; 4] + Sync)) -> Split {
+    if hi - lo == 1 {
+        let [a, b, p, q] = term(lo);
+        let t = a.multiply(&p);
+        return Split { p, q, b, t };
+    }
+    let mid = lo + (hi - lo) / 2;
+    // Each term counts as about a limb of work.
+    let (l, r) = parallel::join(
+        (hi - lo) as usize,
+        || split(lo, mid, term),
+        || split(mid, hi, term),
+    );
+    // T = Br Qr Tl + Bl Pl Tr
+    let t =
+        r.b.multiply(&r.q)
+            .multiply(&l.t)
+            .add(&l.b.multiply(&l.p).multiply(&r.t));
+    Split {
+        p: l.p.multiply(&r.p),
+        q: l.q.multiply(&r.q),
+        b: l.b.multiply(&r.b),
+        t,
+    }
+}
+
+// The series summed over its first `terms` terms, times 10^prec, truncated.
+fn sum(terms: u64, prec: usize, term: &(impl Fn(u64) -> [BigBcd; 4] + Sync)) -> BigBcd {
+    let s = split(0, terms, term);
+    s.t.shl10(prec).divide(&s.b.multiply(&s.q)).unwrap()
+}
+
+// pi, by the Chudnovsky series
+//   426880 sqrt(10005) / pi = sum (-1)^k (6k)! (13591409 + 545140134 k)
+//                                  / ((3k)! (k!)^3 640320^(3k))
+// which gains about 14 digits a term.
+pub fn pi(n: usize) -> BigBcd {
+    truncated(n, |prec| {
+        let terms = prec as u64 / 14 + 2;
+        // The ratio of consecutive terms is p(k) / q(k).
+        let term = |k: u64| {
+            let (p, q) = if k == 0 {
+                (1, 1)
+            } else {
+                let k = k as i128;
+                let p = -(6 * k - 5) * (2 * k - 1) * (6 * k - 1);
+                (p, k * k * k * 10939058860032000)
+            };
+            let a = 13591409 + 545140134 * k as i128;
+            [a, 1, p, q].map(BigBcd::from)
+        };
+        let s = split(0, terms, &term);
+        let sqrt = BigBcd::from(10005).shl10(2 * prec).isqrt().unwrap().0;
+        let mut num = sqrt.multiply(&s.q);
+        num.mul_small_assign(426880);
+        num.divide(&s.t).unwrap()
+    })
+}
+
+// e = sum 1 / k!
+pub fn e(n: usize) -> BigBcd {
+    truncated(n, |prec| {
+        // Enough terms that the first one left out is below 10^-prec.
+        let (mut terms, mut digits) = (1, 0.0);
+        while digits < prec as f64 + 1.0 {
+            digits += (terms as f64).log10();
+            terms += 1;
+        }
+        let term = |k: u64| [1, 1, 1, k.max(1)].map(BigBcd::from);
+        sum(terms, prec, &term)
+    })
+}
+
+// atanh(1 / x) * 10^prec, truncated, from
+//   atanh(1 / x) = sum 1 / ((2k + 1) x^(2k + 1))
+fn atanh_inv(x: u64, prec: usize) -> BigBcd {
+    let terms = (prec as f64 / (2.0 * (x as f64).log10())) as u64 + 2;
+    let term = |k: u64| {
+        let q = if k == 0 { x } else { x * x };
+        [1, 2 * k + 1, 1, q].map(BigBcd::from)
+    };
+    sum(terms, prec, &term)
+}
+
+// ln 2 = 18 atanh(1/26) - 2 atanh(1/4801) + 8 atanh(1/8749)
+pub fn ln2(n: usize) -> BigBcd {
+    truncated(n, |prec| {
+        let mut res = atanh_inv(26, prec);
+        res.mul_small_assign(18);
+        let mut b = atanh_inv(4801, prec);
+        b.mul_small_assign(2);
+        let mut c = atanh_inv(8749, prec);
+        c.mul_small_assign(8);
+        res.subtract(&b).add(&c)
+    })
+}
+
+// sqrt(2), which isqrt already truncates exactly.
+pub fn sqrt2(n: usize) -> BigBcd {
+    BigBcd::from(2).shl10(2 * n).isqrt().unwrap().0
+}
+
+// The golden ratio (1 + sqrt 5) / 2. Halving 10^n + floor(sqrt(5) 10^n)
+// truncates the same way as halving 10^n + sqrt(5) 10^n.
+pub fn phi(n: usize) -> BigBcd {
+    let mut res = BigBcd::from(5).shl10(2 * n).isqrt().unwrap().0;
+    res.add_mag_assign(&BigBcd::pow10(n));
+    res.div_rem_small_assign(2);
+    res
+}
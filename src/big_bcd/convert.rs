@@ -0,0 +1,177 @@
+// Conversions between BigBcd and the primitive integer and float types.
+//
+// Integers go both ways exactly, failing only when a BigBcd is out of the
+// primitive's range. Floats are expanded exactly from their binary value, and
+// converted back with round-to-nearest-even.
+
+use super::radix::to_chunks;
+use super::{BigBcd, BASE};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromBigBcdError;
+
+impl fmt::Display for TryFromBigBcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BigBcd out of range for the target type")
+    }
+}
+
+impl Error for TryFromBigBcdError {}
+
+// A primitive integer as a magnitude and sign.
+pub(super) trait Primitive: Copy {
+    fn split(self) -> (u128, bool);
+
+    // The inverse of `split`, if the value fits.
+    fn join(mag: u128, neg: bool) -> Option<Self>;
+}
+
+macro_rules! primitive_unsigned {
+    ($($t:ty)*) => {$(
+        impl Primitive for $t {
+            fn split(self) -> (u128, bool) {
+                (self as u128, false)
+            }
+
+            fn join(mag: u128, neg: bool) -> Option<Self> {
+                if neg {
+                    None
+                } else {
+                    <$t>::try_from(mag).ok()
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! primitive_signed {
+    ($($t:ty)*) => {$(
+        impl Primitive for $t {
+            fn split(self) -> (u128, bool) {
+                (self.unsigned_abs() as u128, self < 0)
+            }
+
+            fn join(mag: u128, neg: bool) -> Option<Self> {
+                if !neg {
+                    <$t>::try_from(mag).ok()
+                } else if mag <= <$t>::MIN.unsigned_abs() as u128 {
+                    // -MIN overflows, but wrapping negation gives MIN back.
+                    Some((mag as $t).wrapping_neg())
+                } else {
+                    None
+                }
+            }
+        }
+    )*};
+}
+
+primitive_unsigned!(u8 u16 u32 u64 u128 usize);
+primitive_signed!(i8 i16 i32 i64 i128 isize);
+
+macro_rules! primitive_conversions {
+    ($($t:ty)*) => {$(
+        impl From<$t> for BigBcd {
+            fn from(x: $t) -> Self {
+                let (mag, neg) = x.split();
+                BigBcd::from_mag(mag, neg)
+            }
+        }
+
+        impl TryFrom<&BigBcd> for $t {
+            type Error = TryFromBigBcdError;
+
+            fn try_from(x: &BigBcd) -> Result<Self, TryFromBigBcdError> {
+                x.to_mag_u128()
+                    .and_then(|mag| <$t>::join(mag, x.neg))
+                    .ok_or(TryFromBigBcdError)
+            }
+        }
+    )*};
+}
+
+primitive_conversions!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+impl BigBcd {
+    // |self| as a u128, if it fits.
+    fn to_mag_u128(&self) -> Option<u128> {
+        let mut res: u128 = 0;
+        for limb in self.limbs.iter().rev() {
+            res = res.checked_mul(BASE as u128)?.checked_add(*limb as u128)?;
+        }
+        Some(res)
+    }
+
+    // The exact value of x with the fraction dropped, so truncated towards
+    // zero like an `as` cast. None for NaN and the infinities.
+    pub fn from_f64(x: f64) -> Option<Self> {
+        if !x.is_finite() {
+            return None;
+        }
+        let bits = x.to_bits();
+        let neg = bits >> 63 == 1;
+        let biased = ((bits >> 52) & 0x7ff) as i64;
+        let frac = bits & ((1 << 52) - 1);
+        let (mant, exp) = if biased == 0 {
+            (frac, -1074)
+        } else {
+            (frac | (1 << 52), biased - 1075)
+        };
+
+        let mut res = if exp >= 0 {
+            let mut res = BigBcd::from(mant);
+            let mut exp = exp as u32;
+            while exp > 0 {
+                let step = exp.min(63);
+                res.mul_small_assign(1 << step);
+                exp -= step;
+            }
+            res
+        } else if exp > -64 {
+            BigBcd::from(mant >> -exp)
+        } else {
+            BigBcd::from(0)
+        };
+        res.neg = neg;
+        res.fix_sign();
+        Some(res)
+    }
+
+    // The nearest f64, ties to even. Values beyond f64::MAX give infinity.
+    pub fn to_f64(&self) -> f64 {
+        // f64::MAX is below 10^309.
+        let mag = if self.num_digits() > 309 {
+            f64::INFINITY
+        } else {
+            mag_to_f64(&to_chunks(self, 1 << 32))
+        };
+        if self.neg {
+            -mag
+        } else {
+            mag
+        }
+    }
+}
+
+// The nearest f64 to the 32-bit words, least significant first. The top 64
+// bits are rounded by the u64 to f64 cast, with any lower bits folded into
+// the last one. That bit is 11 places below the rounding point, so it only
+// breaks ties, and scaling by a power of two afterwards is exact.
+fn mag_to_f64(words: &[u64]) -> f64 {
+    let bit = |i: usize| (words[i / 32] >> (i % 32)) & 1;
+    let len = match words.last() {
+        Some(&top) => words.len() * 32 - (top as u32).leading_zeros() as usize,
+        None => return 0.0,
+    };
+    let shift = len.saturating_sub(64);
+
+    let mut top = (shift..len).rev().fold(0u64, |acc, i| (acc << 1) | bit(i));
+    let sticky = words[..shift / 32].iter().any(|&w| w != 0)
+        || words[shift / 32] & ((1 << (shift % 32)) - 1) != 0;
+    if sticky {
+        top |= 1;
+    }
+    top as f64 * 2f64.powi(shift as i32)
+}
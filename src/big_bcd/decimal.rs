@@ -0,0 +1,552 @@
+// Arbitrary-precision decimals: a BigBcd coefficient scaled by a power of
+// ten, so `coeff * 10^-scale`. Addition, subtraction and multiplication are
+// exact. Division and rescaling round with an explicit RoundingMode.
+//
+// The scale is part of the representation but not of the value: 1.5 and
+// 1.50 compare equal, and only differ in how they print.
+
+use super::{BigBcd, ParseError};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    // To the nearest, ties to the even neighbour.
+    HalfEven,
+    // To the nearest, ties away from zero.
+    HalfUp,
+    // To the nearest, ties towards zero.
+    HalfDown,
+    // Towards positive infinity.
+    Ceiling,
+    // Towards negative infinity.
+    Floor,
+    // Towards zero, i.e. truncation.
+    Down,
+    // Away from zero.
+    Up,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalError {
+    DivisionByZero,
+    // A result's scale, or a power of ten needed to reach it, does not fit in
+    // an i64.
+    ScaleOutOfRange,
+}
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalError::DivisionByZero => write!(f, "attempt to divide BigDecimal by zero"),
+            DecimalError::ScaleOutOfRange => write!(f, "BigDecimal scale out of range"),
+        }
+    }
+}
+
+impl Error for DecimalError {}
+
+fn to_scale(scale: i128) -> Result<i64, DecimalError> {
+    i64::try_from(scale).map_err(|_| DecimalError::ScaleOutOfRange)
+}
+
+#[derive(Clone, Debug)]
+pub struct BigDecimal {
+    coeff: BigBcd,
+    scale: i64,
+}
+
+// n / d rounded to an integer, for d != 0.
+pub(super) fn div_round(n: &BigBcd, d: &BigBcd, mode: RoundingMode) -> BigBcd {
+    let (mut q, r) = n.div_rem(d).unwrap();
+    if r.is_zero() {
+        return q;
+    }
+    let neg = n.neg != d.neg;
+    let mut twice = r.abs();
+    twice.mul_small_assign(2);
+    let half = twice.cmp_mag(d);
+    let away = match mode {
+        RoundingMode::HalfEven => {
+            half == Ordering::Greater || (half == Ordering::Equal && q.limbs[0] % 2 == 1)
+        }
+        RoundingMode::HalfUp => half != Ordering::Less,
+        RoundingMode::HalfDown => half == Ordering::Greater,
+        RoundingMode::Ceiling => !neg,
+        RoundingMode::Floor => neg,
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+    };
+    if away {
+        q.add_signed_assign(&BigBcd::from(1), neg);
+    }
+    q
+}
+
+impl BigDecimal {
+    // coeff * 10^-scale
+    pub fn new(coeff: BigBcd, scale: i64) -> Self {
+        BigDecimal { coeff, scale }
+    }
+
+    pub fn coefficient(&self) -> &BigBcd {
+        &self.coeff
+    }
+
+    pub fn scale(&self) -> i64 {
+        self.scale
+    }
+
+    // The number of significant digits in the coefficient.
+    pub fn precision(&self) -> usize {
+        self.coeff.num_digits()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeff.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.coeff.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        BigDecimal::new(self.coeff.abs(), self.scale)
+    }
+
+    pub fn negated(&self) -> Self {
+        BigDecimal::new(self.coeff.negated(), self.scale)
+    }
+
+    // The same value with the given scale, rounding if digits are dropped.
+    pub fn with_scale(&self, scale: i64, mode: RoundingMode) -> Self {
+        let diff = scale as i128 - self.scale as i128;
+        let coeff = if diff >= 0 {
+            self.coeff.shl10(diff as usize)
+        } else {
+            // Dividing by anything above 10^(digits + 1) rounds the same way.
+            let k = (-diff).min(self.coeff.num_digits() as i128 + 1);
+            div_round(&self.coeff, &BigBcd::pow10(k as usize), mode)
+        };
+        BigDecimal::new(coeff, scale)
+    }
+
+    // Rounds to at most `digits` significant digits. Fails if dropping them
+    // takes the scale below i64::MIN.
+    pub fn round(&self, digits: usize, mode: RoundingMode) -> Result<Self, DecimalError> {
+        let (coeff, scale) = self.round_parts(digits, mode);
+        Ok(BigDecimal::new(coeff, to_scale(scale)?))
+    }
+
+    // The coefficient and scale of `round`, with the scale in an i128.
+    fn round_parts(&self, digits: usize, mode: RoundingMode) -> (BigBcd, i128) {
+        let n = self.precision();
+        if n <= digits {
+            return (self.coeff.clone(), self.scale as i128);
+        }
+        let mut coeff = div_round(&self.coeff, &BigBcd::pow10(n - digits), mode);
+        let mut scale = self.scale as i128 - (n - digits) as i128;
+        // Rounding up can carry into a new digit, as in 9.99 -> 10.0.
+        if coeff.num_digits() > digits.max(1) {
+            coeff.div_rem_small_assign(10);
+            scale -= 1;
+        }
+        (coeff, scale)
+    }
+
+    // The same value with trailing zeros of the coefficient removed, or as
+    // many as the scale can drop before it reaches i64::MIN. Zero has scale
+    // 0.
+    pub fn normalized(&self) -> Self {
+        if self.is_zero() {
+            return BigDecimal::new(BigBcd::from(0), 0);
+        }
+        let mut res = self.clone();
+        while res.scale > i64::MIN && res.coeff.limbs[0].is_multiple_of(10) {
+            res.coeff.div_rem_small_assign(10);
+            res.scale -= 1;
+        }
+        res
+    }
+
+    // Both coefficients at the larger of the two scales.
+    fn aligned(&self, other: &Self) -> (BigBcd, BigBcd, i64) {
+        let scale = self.scale.max(other.scale);
+        let a = self.with_scale(scale, RoundingMode::Down).coeff;
+        let b = other.with_scale(scale, RoundingMode::Down).coeff;
+        (a, b, scale)
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let (a, b, scale) = self.aligned(other);
+        BigDecimal::new(a.add(&b), scale)
+    }
+
+    pub fn subtract(&self, other: &Self) -> Self {
+        let (a, b, scale) = self.aligned(other);
+        BigDecimal::new(a.subtract(&b), scale)
+    }
+
+    // Fails if the scales sum past the range of an i64.
+    pub fn multiply(&self, other: &Self) -> Result<Self, DecimalError> {
+        let scale = to_scale(self.scale as i128 + other.scale as i128)?;
+        Ok(BigDecimal::new(self.coeff.multiply(&other.coeff), scale))
+    }
+
+    // self / other rounded to the given scale.
+    pub fn divide_to_scale(
+        &self,
+        other: &Self,
+        scale: i64,
+        mode: RoundingMode,
+    ) -> Result<Self, DecimalError> {
+        if other.is_zero() {
+            return Err(DecimalError::DivisionByZero);
+        }
+        // coeff = self.coeff * 10^shift / other.coeff
+        let shift = scale as i128 - self.scale as i128 + other.scale as i128;
+        let (n, d) = if shift >= 0 {
+            let shift = usize::try_from(shift).map_err(|_| DecimalError::ScaleOutOfRange)?;
+            (self.coeff.shl10(shift), other.coeff.clone())
+        } else {
+            // Dividing by anything above 10^(digits + 1) rounds the same way.
+            let k = (-shift).min(self.coeff.num_digits() as i128 + 1);
+            (self.coeff.clone(), other.coeff.shl10(k as usize))
+        };
+        Ok(BigDecimal::new(div_round(&n, &d, mode), scale))
+    }
+
+    // self / other rounded to `digits` significant digits.
+    pub fn divide(
+        &self,
+        other: &Self,
+        digits: usize,
+        mode: RoundingMode,
+    ) -> Result<Self, DecimalError> {
+        if other.is_zero() {
+            return Err(DecimalError::DivisionByZero);
+        }
+        if self.is_zero() {
+            return Ok(BigDecimal::new(BigBcd::from(0), 0));
+        }
+        // The quotient's leading digit is in the place of the difference of
+        // the adjusted exponents, or one lower if the digits of |self| sort
+        // below those of |other|.
+        let lead = |x: &BigBcd| {
+            let mut d = x.to_digits_be();
+            while d.last() == Some(&0) {
+                d.pop();
+            }
+            d
+        };
+        let mut e = self.adjusted() - other.adjusted();
+        if lead(&self.coeff) < lead(&other.coeff) {
+            e -= 1;
+        }
+        let scale = to_scale(digits as i128 - 1 - e)?;
+        self.divide_to_scale(other, scale, mode)?
+            .round(digits, mode)
+    }
+
+    // The exponent of the leading digit, so self lies in
+    // [10^adjusted, 10^(adjusted + 1)) in magnitude. An i128, since it can
+    // lie just outside the range of the scale.
+    fn adjusted(&self) -> i128 {
+        self.precision() as i128 - 1 - self.scale as i128
+    }
+
+    // Scientific notation, with the mantissa rounded to `precision`
+    // fractional digits if given.
+    fn fmt_exp(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        e: char,
+        precision: Option<usize>,
+    ) -> fmt::Result {
+        let (coeff, scale) = match precision {
+            Some(p) => self.round_parts(p + 1, RoundingMode::HalfEven),
+            None => (self.coeff.clone(), self.scale as i128),
+        };
+        let mut digits = coeff.abs().to_string();
+        let adjusted = digits.len() as i128 - 1 - scale;
+        if let Some(p) = precision {
+            // Trailing zeros don't move the leading digit, so `adjusted`
+            // stays the exponent.
+            digits.push_str(&"0".repeat((p + 1).saturating_sub(digits.len())));
+        }
+        let mut s = digits[..1].to_string();
+        if digits.len() > 1 {
+            s.push('.');
+            s.push_str(&digits[1..]);
+        }
+        s.push(e);
+        s.push_str(&adjusted.to_string());
+        f.pad_integral(!coeff.is_negative(), "", &s)
+    }
+}
+
+impl From<BigBcd> for BigDecimal {
+    fn from(x: BigBcd) -> Self {
+        BigDecimal::new(x, 0)
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigDecimal {}
+
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Aligning the scales can take as many digits as they differ by, so
+        // the signs and then the places of the leading digits decide first.
+        let sign = |x: &Self| x.coeff.cmp(&BigBcd::from(0));
+        let ord = sign(self).cmp(&sign(other));
+        if ord != Ordering::Equal || self.is_zero() {
+            return ord;
+        }
+        let place = |x: &Self| x.precision() as i128 - x.scale as i128;
+        let ord = place(self).cmp(&place(other));
+        if ord != Ordering::Equal {
+            return if self.is_negative() {
+                ord.reverse()
+            } else {
+                ord
+            };
+        }
+        let (a, b, _) = self.aligned(other);
+        a.cmp(&b)
+    }
+}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Equal values have the same normalized form, so 1.50 and 1.5 hash alike.
+impl Hash for BigDecimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let n = self.normalized();
+        n.coeff.hash(state);
+        n.scale.hash(state);
+    }
+}
+
+// The most zeros Display pads a number with before it switches to scientific
+// notation, which can take a scale anywhere in i64.
+const MAX_PLAIN_ZEROS: i128 = 1000;
+
+// Plain notation, keeping every digit of the scale: 1.50, -0.003, 1200. A
+// number that would need more than MAX_PLAIN_ZEROS zeros of padding prints
+// all of its digits in scientific notation instead, as in 1.50e-2000.
+impl fmt::Display for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.coeff.abs().to_string();
+        let zeros = if self.scale <= 0 && !self.is_zero() {
+            -(self.scale as i128)
+        } else {
+            self.scale as i128 - digits.len() as i128
+        };
+        if zeros > MAX_PLAIN_ZEROS {
+            return self.fmt_exp(f, 'e', None);
+        }
+        let s = if self.scale <= 0 {
+            if self.is_zero() {
+                digits
+            } else {
+                digits + &"0".repeat(-self.scale as usize)
+            }
+        } else if self.scale as usize >= digits.len() {
+            format!(
+                "0.{}{}",
+                "0".repeat(self.scale as usize - digits.len()),
+                digits
+            )
+        } else {
+            let point = digits.len() - self.scale as usize;
+            format!("{}.{}", &digits[..point], &digits[point..])
+        };
+        f.pad_integral(!self.is_negative(), "", &s)
+    }
+}
+
+// Scientific notation, such as 1.2345e-10. A precision rounds the mantissa
+// to that many fractional digits, ties to even.
+impl fmt::LowerExp for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_exp(f, 'e', f.precision())
+    }
+}
+
+impl fmt::UpperExp for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_exp(f, 'E', f.precision())
+    }
+}
+
+// The exponent after the 'e': an optional sign and then ASCII digits only,
+// with no whitespace or separators. `start` is its position in the input.
+fn parse_exponent(s: &str, start: usize) -> Result<i64, ParseError> {
+    let (neg, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    if digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let offset = start + s.len() - digits.len();
+    let mut e: i128 = 0;
+    for (i, c) in digits.char_indices() {
+        let d = match c {
+            '0'..='9' => c as u8 - b'0',
+            _ => {
+                return Err(ParseError::InvalidDigit {
+                    position: offset + i,
+                    found: c,
+                })
+            }
+        };
+        // Anything past 2^63 is out of range anyway, so cap e rather than let
+        // a long run of digits overflow it.
+        e = (e * 10 + d as i128).min(1 << 64);
+    }
+    i64::try_from(if neg { -e } else { e }).map_err(|_| ParseError::ExponentOutOfRange)
+}
+
+// Accepts an optional sign, digits with an optional decimal point, and an
+// optional exponent: "12", "-0.50", ".5", "1.2345e-10", "3E+4". The scale
+// is the number of fractional digits minus the exponent.
+impl FromStr for BigDecimal {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let start = s.len() - s.trim_start().len();
+        let body = s.trim();
+        let (mantissa, exp) = match body.find(['e', 'E']) {
+            Some(i) => (&body[..i], Some(i + 1)),
+            None => (body, None),
+        };
+
+        let mut neg = false;
+        let mut digits = Vec::with_capacity(mantissa.len());
+        let mut frac: Option<i64> = None;
+        for (i, c) in mantissa.char_indices() {
+            match c {
+                '-' | '+' if i == 0 => neg = c == '-',
+                '.' if frac.is_none() => frac = Some(0),
+                '0'..='9' => {
+                    digits.push(c as u8 - b'0');
+                    frac = frac.map(|n| n + 1);
+                }
+                _ => {
+                    return Err(ParseError::InvalidDigit {
+                        position: start + i,
+                        found: c,
+                    })
+                }
+            }
+        }
+        if digits.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let exp = match exp {
+            Some(i) => parse_exponent(&body[i..], start + i)?,
+            None => 0,
+        };
+        let scale = frac
+            .unwrap_or(0)
+            .checked_sub(exp)
+            .ok_or(ParseError::ExponentOutOfRange)?;
+        Ok(BigDecimal::new(BigBcd::from_digits_be(&digits, neg), scale))
+    }
+}
+
+impl Neg for BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> BigDecimal {
+        self.negated()
+    }
+}
+
+impl Neg for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> BigDecimal {
+        self.negated()
+    }
+}
+
+// Every owned/borrowed combination of a binary operator, and its `op=`, from
+// the method of the same name.
+macro_rules! decimal_binop {
+    ($imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident, $f:ident) => {
+        impl $imp<&BigDecimal> for &BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: &BigDecimal) -> BigDecimal {
+                BigDecimal::$f(self, other)
+            }
+        }
+
+        impl $imp<BigDecimal> for &BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: BigDecimal) -> BigDecimal {
+                BigDecimal::$f(self, &other)
+            }
+        }
+
+        impl $imp<&BigDecimal> for BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: &BigDecimal) -> BigDecimal {
+                BigDecimal::$f(&self, other)
+            }
+        }
+
+        impl $imp<BigDecimal> for BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: BigDecimal) -> BigDecimal {
+                BigDecimal::$f(&self, &other)
+            }
+        }
+
+        impl $assign_imp<&BigDecimal> for BigDecimal {
+            fn $assign_method(&mut self, other: &BigDecimal) {
+                *self = BigDecimal::$f(self, other);
+            }
+        }
+
+        impl $assign_imp<BigDecimal> for BigDecimal {
+            fn $assign_method(&mut self, other: BigDecimal) {
+                *self = BigDecimal::$f(self, &other);
+            }
+        }
+    };
+}
+
+impl BigDecimal {
+    // Like the primitive operators, `*` panics on overflow, here of the
+    // scale.
+    fn mul_or_panic(&self, other: &Self) -> Self {
+        self.multiply(other)
+            .expect("attempt to multiply with scale overflow")
+    }
+}
+
+decimal_binop!(Add, add, AddAssign, add_assign, add);
+decimal_binop!(Sub, sub, SubAssign, sub_assign, subtract);
+decimal_binop!(Mul, mul, MulAssign, mul_assign, mul_or_panic);
@@ -0,0 +1,227 @@
+// Digit-level access to the magnitude of a BigBcd: iteration, single-digit
+// reads and writes, digit sums, reversal, rotation, permutation, and shifts
+// by powers of ten. Positions count from the least significant digit, and
+// the sign is left alone throughout.
+
+use super::{BigBcd, LIMB_DIGITS};
+use std::iter::Rev;
+
+// The decimal digits of |x| least significant first, or most significant
+// first when reversed. Zero has the single digit 0.
+#[derive(Clone, Debug)]
+pub struct Digits<'a> {
+    x: &'a BigBcd,
+    front: usize,
+    back: usize,
+}
+
+impl Iterator for Digits<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.x.digit(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for Digits<'_> {
+    fn next_back(&mut self) -> Option<u8> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.x.digit(self.back))
+    }
+}
+
+impl ExactSizeIterator for Digits<'_> {}
+
+// Every distinct arrangement of the digits of |x|, in increasing order of
+// magnitude and with the sign of x. Arrangements with leading zeros are
+// included, as shorter numbers.
+#[derive(Clone, Debug)]
+pub struct DigitPermutations {
+    digits: Vec<u8>,
+    neg: bool,
+    done: bool,
+}
+
+impl Iterator for DigitPermutations {
+    type Item = BigBcd;
+
+    fn next(&mut self) -> Option<BigBcd> {
+        if self.done {
+            return None;
+        }
+        let res = BigBcd::from_digits_be(&self.digits, self.neg);
+        self.done = !next_permutation(&mut self.digits);
+        Some(res)
+    }
+}
+
+// Steps d to the next arrangement in lexicographic order, returning false
+// if it was already the last.
+fn next_permutation(d: &mut [u8]) -> bool {
+    let i = match (1..d.len()).rev().find(|&i| d[i - 1] < d[i]) {
+        Some(i) => i,
+        None => return false,
+    };
+    let j = (i..d.len()).rev().find(|&j| d[j] > d[i - 1]).unwrap();
+    d.swap(i - 1, j);
+    d[i..].reverse();
+    true
+}
+
+impl BigBcd {
+    // Number of decimal digits in |self|. Zero has one digit.
+    pub fn num_digits(&self) -> usize {
+        let mut top = *self.limbs.last().unwrap();
+        let mut top_digits = 1;
+        while top >= 10 {
+            top /= 10;
+            top_digits += 1;
+        }
+        (self.limbs.len() - 1) * LIMB_DIGITS + top_digits
+    }
+
+    // The decimal digit of |self| at position i, counting from the least
+    // significant. Digits past the end are 0.
+    pub fn digit(&self, i: usize) -> u8 {
+        match self.limbs.get(i / LIMB_DIGITS) {
+            Some(limb) => ((limb / 10u32.pow((i % LIMB_DIGITS) as u32)) % 10) as u8,
+            None => 0,
+        }
+    }
+
+    // Sets the digit at position i, extending |self| with zeros if needed.
+    // Panics if d is not a decimal digit.
+    pub fn set_digit(&mut self, i: usize, d: u8) {
+        assert!(d < 10, "{} is not a decimal digit", d);
+        let idx = i / LIMB_DIGITS;
+        if idx >= self.limbs.len() {
+            self.limbs.resize(idx + 1, 0);
+        }
+        let p = 10u32.pow((i % LIMB_DIGITS) as u32);
+        let limb = &mut self.limbs[idx];
+        *limb -= (*limb / p % 10) * p;
+        *limb += d as u32 * p;
+        self.trim();
+    }
+
+    pub fn digits_le(&self) -> Digits<'_> {
+        Digits {
+            x: self,
+            front: 0,
+            back: self.num_digits(),
+        }
+    }
+
+    pub fn digits_be(&self) -> Rev<Digits<'_>> {
+        self.digits_le().rev()
+    }
+
+    pub fn digit_sum(&self) -> u64 {
+        self.limbs
+            .iter()
+            .map(|&limb| {
+                let (mut limb, mut sum) = (limb, 0);
+                while limb > 0 {
+                    sum += (limb % 10) as u64;
+                    limb /= 10;
+                }
+                sum
+            })
+            .sum()
+    }
+
+    // The repeated digit sum, down to a single digit. BASE is 1 mod 9, so
+    // this is |self| mod 9, with 9 in place of 0 for non-zero values.
+    pub fn digital_root(&self) -> u8 {
+        if self.is_zero() {
+            return 0;
+        }
+        let m = self.limbs.iter().map(|&limb| limb as u64 % 9).sum::<u64>() % 9;
+        if m == 0 {
+            9
+        } else {
+            m as u8
+        }
+    }
+
+    // The digits of |self| in reverse order, so 1200 gives 21.
+    pub fn reverse_digits(&self) -> Self {
+        let mut digits = self.to_digits_be();
+        digits.reverse();
+        BigBcd::from_digits_be(&digits, self.neg)
+    }
+
+    pub fn is_palindrome(&self) -> bool {
+        let digits = self.to_digits_be();
+        digits.iter().eq(digits.iter().rev())
+    }
+
+    // Moves the k leading digits to the end, as in 12345 -> 23451. Zeros
+    // that end up leading are dropped.
+    pub fn rotate_digits_left(&self, k: usize) -> Self {
+        let mut digits = self.to_digits_be();
+        let n = digits.len();
+        digits.rotate_left(k % n);
+        BigBcd::from_digits_be(&digits, self.neg)
+    }
+
+    // Moves the k trailing digits to the front, as in 12345 -> 51234.
+    pub fn rotate_digits_right(&self, k: usize) -> Self {
+        let mut digits = self.to_digits_be();
+        let n = digits.len();
+        digits.rotate_right(k % n);
+        BigBcd::from_digits_be(&digits, self.neg)
+    }
+
+    pub fn digit_permutations(&self) -> DigitPermutations {
+        let mut digits = self.to_digits_be();
+        digits.sort_unstable();
+        DigitPermutations {
+            digits,
+            neg: self.neg,
+            done: false,
+        }
+    }
+
+    // self * 10^k, by moving whole limbs and one small multiplication.
+    pub fn shl10(&self, k: usize) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let mut limbs = vec![0; k / LIMB_DIGITS];
+        limbs.extend_from_slice(&self.limbs);
+        let mut res = BigBcd {
+            limbs,
+            neg: self.neg,
+        };
+        res.mul_small_assign(10u64.pow((k % LIMB_DIGITS) as u32));
+        res
+    }
+
+    // self / 10^k, truncated towards zero, by dropping whole limbs and one
+    // small division.
+    pub fn shr10(&self, k: usize) -> Self {
+        let skip = k / LIMB_DIGITS;
+        if skip >= self.limbs.len() {
+            return BigBcd::from(0);
+        }
+        let mut res = BigBcd {
+            limbs: self.limbs[skip..].to_vec(),
+            neg: self.neg,
+        };
+        res.div_rem_small_assign(10u64.pow((k % LIMB_DIGITS) as u32));
+        res
+    }
+}
@@ -0,0 +1,193 @@
+// Division of magnitudes. Every signed division goes through `div_rem_mag`.
+//
+// One-limb divisors use short division and medium ones Knuth's Algorithm D.
+// Long divisors with long quotients use a reciprocal computed by Newton
+// iteration, so the division costs a few multiplications per block of
+// quotient limbs instead of O(n * m) limb operations.
+
+use super::{BigBcd, BASE};
+use std::cmp::Ordering;
+
+// Length in limbs of the divisor, and of the quotient, from which Newton
+// division beats Algorithm D.
+const NEWTON_THRESHOLD: usize = 2000;
+
+// (|a| / |b|, |a| % |b|). b must not be zero.
+pub(super) fn div_rem_mag(a: &BigBcd, b: &BigBcd) -> (BigBcd, BigBcd) {
+    debug_assert!(!b.is_zero());
+    let mut rem = a.abs();
+    if a.cmp_mag(b) == Ordering::Less {
+        return (BigBcd::from(0), rem);
+    }
+    if b.limbs.len() == 1 {
+        let r = rem.div_rem_small_assign(b.limbs[0] as u64);
+        return (rem, BigBcd::from_mag(r as u128, false));
+    }
+
+    let (n, m) = (b.limbs.len(), a.limbs.len() - b.limbs.len());
+    if n < NEWTON_THRESHOLD || m < NEWTON_THRESHOLD {
+        let (q, r) = knuth(&a.limbs, &b.limbs);
+        (from_limbs(q), from_limbs(r))
+    } else {
+        newton(&rem, &b.abs())
+    }
+}
+
+// Knuth's Algorithm D (TAOCP 4.3.1). Requires v.len() >= 2, no leading zero
+// limb in v and u >= v. Returns the quotient and remainder limbs.
+fn knuth(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let base = BASE as u64;
+    let n = v.len();
+    let m = u.len() - n;
+
+    // Scale both operands so the top limb of the divisor is at least
+    // BASE / 2, which keeps each quotient estimate within two of the truth.
+    let f = base / (v[n - 1] as u64 + 1);
+    let v = scale(v, f);
+    let mut u = scale(u, f);
+    u.resize(m + n + 1, 0);
+    let (v_top, v_next) = (v[n - 1] as u64, v[n - 2] as u64);
+
+    let mut q = vec![0; m + 1];
+    for j in (0..=m).rev() {
+        let top = u[j + n] as u64 * base + u[j + n - 1] as u64;
+        let mut q_hat = top / v_top;
+        let mut r_hat = top % v_top;
+        while q_hat >= base || q_hat * v_next > r_hat * base + u[j + n - 2] as u64 {
+            q_hat -= 1;
+            r_hat += v_top;
+            if r_hat >= base {
+                break;
+            }
+        }
+
+        // u[j..=j + n] -= q_hat * v
+        let mut borrow = 0;
+        let mut carry = 0;
+        for (x, &y) in u[j..j + n].iter_mut().zip(v.iter()) {
+            let prod = q_hat * y as u64 + carry;
+            carry = prod / base;
+            let mut curr = *x as i64 - (prod % base) as i64 - borrow;
+            borrow = (curr < 0) as i64;
+            curr += borrow * base as i64;
+            *x = curr as u32;
+        }
+        let curr = u[j + n] as i64 - carry as i64 - borrow;
+        if curr >= 0 {
+            u[j + n] = curr as u32;
+        } else {
+            // q_hat was one too large: add v back, dropping the final carry.
+            u[j + n] = (curr + base as i64) as u32;
+            q_hat -= 1;
+            let mut carry = 0;
+            for (x, &y) in u[j..j + n].iter_mut().zip(v.iter()) {
+                let curr = *x + y + carry;
+                carry = (curr >= BASE) as u32;
+                *x = curr - carry * BASE;
+            }
+            u[j + n] = ((u[j + n] + carry) as u64 % base) as u32;
+        }
+        q[j] = q_hat as u32;
+    }
+
+    // Undo the scaling of the remainder.
+    u.truncate(n);
+    let mut rem: u64 = 0;
+    for x in u.iter_mut().rev() {
+        let curr = rem * base + *x as u64;
+        *x = (curr / f) as u32;
+        rem = curr % f;
+    }
+    (q, u)
+}
+
+// x * f for f < BASE. Leading zeros of the result are dropped.
+fn scale(x: &[u32], f: u64) -> Vec<u32> {
+    let mut res = Vec::with_capacity(x.len() + 1);
+    let mut carry = 0;
+    for &limb in x {
+        let curr = limb as u64 * f + carry;
+        res.push((curr % BASE as u64) as u32);
+        carry = curr / BASE as u64;
+    }
+    if carry > 0 {
+        res.push(carry as u32);
+    }
+    res
+}
+
+// Divides a in blocks of n = |b| limbs, from the top. Each block, with the
+// remainder of the previous one above it, is below b * BASE^n, so its
+// quotient is a single block computed from the reciprocal.
+fn newton(a: &BigBcd, b: &BigBcd) -> (BigBcd, BigBcd) {
+    let n = b.limbs.len();
+    let recip = reciprocal(b);
+
+    let mut quot = vec![0; a.limbs.len()];
+    let mut rem = BigBcd::from(0);
+    for (i, chunk) in a.limbs.chunks(n).enumerate().rev() {
+        let mut limbs = chunk.to_vec();
+        limbs.resize(n, 0);
+        limbs.extend_from_slice(&rem.limbs);
+        let curr = from_limbs(limbs);
+
+        let mut q = shr(&curr.multiply(&recip), 2 * n);
+        rem = curr.subtract(&q.multiply(b));
+        while rem.cmp_mag(b) != Ordering::Less {
+            rem.add_signed_assign(b, true);
+            q.add_mag_assign(&BigBcd::from(1));
+        }
+        for (dst, &limb) in quot[i * n..].iter_mut().zip(q.limbs.iter()) {
+            *dst = limb;
+        }
+    }
+    (from_limbs(quot), rem)
+}
+
+// floor(BASE^2n / b) for an n-limb b.
+//
+// The reciprocal of the top half of b, shifted into place, is correct to
+// about half the limbs. One Newton step x += x * (BASE^2n - b * x) / BASE^2n
+// doubles that, and a few unit corrections make the result exact.
+pub(super) fn reciprocal(b: &BigBcd) -> BigBcd {
+    let n = b.limbs.len();
+    let one = shl(&BigBcd::from(1), 2 * n);
+    if n < NEWTON_THRESHOLD {
+        return div_rem_mag(&one, b).0;
+    }
+
+    let h = n / 2 + 2;
+    let mut x = shl(&reciprocal(&shr(b, n - h)), n - h);
+    let err = one.subtract(&b.multiply(&x));
+    let step = shr(&x.multiply(&err), 2 * n);
+    x.add_signed_assign(&step, err.neg);
+
+    let mut err = one.subtract(&b.multiply(&x));
+    while err.neg {
+        x.add_signed_assign(&BigBcd::from(1), true);
+        err.add_signed_assign(b, false);
+    }
+    while err.cmp_mag(b) != Ordering::Less {
+        x.add_mag_assign(&BigBcd::from(1));
+        err.add_signed_assign(b, true);
+    }
+    x
+}
+
+// |x| * BASE^k
+pub(super) fn shl(x: &BigBcd, k: usize) -> BigBcd {
+    let mut limbs = vec![0; k];
+    limbs.extend_from_slice(&x.limbs);
+    from_limbs(limbs)
+}
+
+// |x| / BASE^k
+pub(super) fn shr(x: &BigBcd, k: usize) -> BigBcd {
+    from_limbs(x.limbs.get(k..).unwrap_or(&[]).to_vec())
+}
+
+pub(super) fn from_limbs(limbs: Vec<u32>) -> BigBcd {
+    let mut res = BigBcd { limbs, neg: false };
+    res.trim();
+    res
+}
@@ -0,0 +1,113 @@
+// Display and scientific notation for BigBcd, and digit grouping.
+//
+// All of them honour the Formatter's width, fill, alignment, `+` and `0`
+// flags. `{:#}` groups the digits in thousands with commas, and `{:e}` prints
+// the significant digits, or `{:.5e}` a mantissa rounded half to even.
+
+use super::{BigBcd, BigDecimal};
+use std::fmt;
+use std::fmt::Write;
+
+// How to split the digits into groups, counting from the least significant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    // 1,234,567
+    Thousands,
+    // A group of three and then groups of two: 12,34,567.
+    Indian,
+    // Groups of the given size, e.g. 4 for 123,4567. Panics on 0.
+    Uniform(usize),
+}
+
+impl Grouping {
+    // The size of the lowest group and of every group after it.
+    fn sizes(self) -> (usize, usize) {
+        match self {
+            Grouping::Thousands => (3, 3),
+            Grouping::Indian => (3, 2),
+            Grouping::Uniform(n) => {
+                assert!(n > 0, "digit groups must not be empty");
+                (n, n)
+            }
+        }
+    }
+}
+
+impl BigBcd {
+    // The decimal digits of |self|.
+    fn mag_string(&self) -> String {
+        let mut s = String::with_capacity(self.limbs.len() * 9);
+        let mut limbs = self.limbs.iter().rev();
+        write!(s, "{}", limbs.next().unwrap()).unwrap();
+        for limb in limbs {
+            write!(s, "{:09}", limb).unwrap();
+        }
+        s
+    }
+
+    // The decimal string with `separator` between groups of digits, as in
+    // to_grouped_string(" ", Grouping::Indian) == "-1 23 45 678".
+    pub fn to_grouped_string(&self, separator: &str, grouping: Grouping) -> String {
+        let mut res = String::new();
+        if self.neg {
+            res.push('-');
+        }
+        res.push_str(&group(&self.mag_string(), separator, grouping));
+        res
+    }
+
+    fn fmt_exp(&self, f: &mut fmt::Formatter<'_>, upper: bool) -> fmt::Result {
+        // Without a precision every significant digit is printed, so trailing
+        // zeros only move the exponent, as for the primitive integers.
+        let x = match f.precision() {
+            Some(_) => BigDecimal::from(self.clone()),
+            None if self.is_zero() => BigDecimal::from(self.clone()),
+            None => {
+                let zeros = self.digits_le().take_while(|&d| d == 0).count();
+                BigDecimal::new(self.shr10(zeros), -(zeros as i64))
+            }
+        };
+        if upper {
+            fmt::UpperExp::fmt(&x, f)
+        } else {
+            fmt::LowerExp::fmt(&x, f)
+        }
+    }
+}
+
+// digits split into groups, least significant first.
+fn group(digits: &str, separator: &str, grouping: Grouping) -> String {
+    let (mut size, rest) = grouping.sizes();
+    let mut groups = vec![];
+    let mut end = digits.len();
+    while end > size {
+        groups.push(&digits[end - size..end]);
+        end -= size;
+        size = rest;
+    }
+    groups.push(&digits[..end]);
+    groups.reverse();
+    groups.join(separator)
+}
+
+impl fmt::Display for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = self.mag_string();
+        if f.alternate() {
+            digits = group(&digits, ",", Grouping::Thousands);
+        }
+        f.pad_integral(!self.neg, "", &digits)
+    }
+}
+
+impl fmt::LowerExp for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_exp(f, false)
+    }
+}
+
+impl fmt::UpperExp for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_exp(f, true)
+    }
+}
@@ -0,0 +1,446 @@
+// The IEEE 754-2008 decimal64 and decimal128 interchange formats, in both the
+// BID (binary integer) and DPD (densely packed decimal) encodings.
+//
+// Each format has a sign bit, a 5-bit combination field, an exponent
+// continuation and a trailing significand. The combination field holds the
+// top two exponent bits and either the leading digit (DPD) or the top bits of
+// the binary coefficient (BID), or marks an infinity or NaN. DPD packs the
+// remaining digits three to a 10-bit declet.
+//
+// Values keep their exact coefficient and exponent, so members of a cohort
+// such as 1.0 and 1.00 encode differently, as do 0 and -0.
+
+use super::decimal::div_round;
+use super::{BigBcd, BigDecimal, ParseError, RoundingMode};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalFormat {
+    Decimal64,
+    Decimal128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalEncoding {
+    Bid,
+    Dpd,
+}
+
+// Equality is on the representation: 0 != -0, 1.0 != 1.00, and NaNs compare
+// by payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IeeeDecimal {
+    // (-1)^neg * coeff * 10^exp, with coeff >= 0.
+    Finite {
+        neg: bool,
+        coeff: BigBcd,
+        exp: i64,
+    },
+    Infinity {
+        neg: bool,
+    },
+    NaN {
+        neg: bool,
+        signaling: bool,
+        payload: BigBcd,
+    },
+}
+
+impl DecimalFormat {
+    fn width(self) -> u32 {
+        match self {
+            DecimalFormat::Decimal64 => 64,
+            DecimalFormat::Decimal128 => 128,
+        }
+    }
+
+    // Digits in the coefficient.
+    fn precision(self) -> u32 {
+        match self {
+            DecimalFormat::Decimal64 => 16,
+            DecimalFormat::Decimal128 => 34,
+        }
+    }
+
+    // Bits in the biased exponent.
+    fn exp_bits(self) -> u32 {
+        match self {
+            DecimalFormat::Decimal64 => 10,
+            DecimalFormat::Decimal128 => 14,
+        }
+    }
+
+    // Bits in the trailing significand.
+    fn trailing_bits(self) -> u32 {
+        self.width() - 4 - self.exp_bits()
+    }
+
+    // Also the negated smallest exponent, that of the subnormals.
+    fn bias(self) -> i64 {
+        match self {
+            DecimalFormat::Decimal64 => 398,
+            DecimalFormat::Decimal128 => 6176,
+        }
+    }
+
+    // The largest exponent. The top two biased exponent bits are never 11.
+    fn max_exp(self) -> i64 {
+        (3 << (self.exp_bits() - 2)) - 1 - self.bias()
+    }
+}
+
+fn mask(bits: u32) -> u128 {
+    (1 << bits) - 1
+}
+
+// The three digits of a declet. All 1024 codes decode, including the 24
+// non-canonical ones.
+fn decode_declet(x: u32) -> u32 {
+    let (pqr, stu, wxy) = ((x >> 7) & 7, (x >> 4) & 7, x & 7);
+    let (r, u, y) = ((x >> 7) & 1, (x >> 4) & 1, x & 1);
+    let pq = ((x >> 8) & 3) << 1;
+    let st = ((x >> 5) & 3) << 1;
+    let (h, t, o) = if x & 8 == 0 {
+        (pqr, stu, wxy)
+    } else {
+        match ((x >> 1) & 3, (x >> 5) & 3) {
+            (0, _) => (pqr, stu, 8 + y),
+            (1, _) => (pqr, 8 + u, st | y),
+            (2, _) => (8 + r, stu, pq | y),
+            (_, 0) => (8 + r, 8 + u, pq | y),
+            (_, 1) => (8 + r, pq | u, 8 + y),
+            (_, 2) => (pqr, 8 + u, 8 + y),
+            _ => (8 + r, 8 + u, 8 + y),
+        }
+    };
+    h * 100 + t * 10 + o
+}
+
+// The canonical declet of d < 1000. Digits below 8 keep three bits, and 8 and
+// 9 keep only their lowest, with the freed bits saying which are which.
+fn encode_declet(d: u32) -> u32 {
+    let (h, t, o) = (d / 100, d / 10 % 10, d % 10);
+    let large = ((h >= 8) as u32) << 2 | ((t >= 8) as u32) << 1 | (o >= 8) as u32;
+    let (h1, t1, o1) = (h & 1, t & 1, o & 1);
+    match large {
+        0b000 => h << 7 | t << 4 | o,
+        0b001 => h << 7 | t << 4 | 0b1000 | o1,
+        0b010 => h << 7 | (o >> 1) << 5 | t1 << 4 | 0b1010 | o1,
+        0b100 => (o >> 1) << 8 | h1 << 7 | t << 4 | 0b1100 | o1,
+        0b011 => h << 7 | 0b10 << 5 | t1 << 4 | 0b1110 | o1,
+        0b101 => (t >> 1) << 8 | h1 << 7 | 0b01 << 5 | t1 << 4 | 0b1110 | o1,
+        0b110 => (o >> 1) << 8 | h1 << 7 | t1 << 4 | 0b1110 | o1,
+        _ => h1 << 7 | 0b11 << 5 | t1 << 4 | 0b1110 | o1,
+    }
+}
+
+// The value of the low `n` declets of `bits`.
+fn from_declets(bits: u128, n: u32) -> u128 {
+    (0..n).rev().fold(0, |acc, i| {
+        acc * 1000 + decode_declet(((bits >> (10 * i)) & 0x3ff) as u32) as u128
+    })
+}
+
+// The low 3n digits of x as n declets.
+fn to_declets(mut x: u128, n: u32) -> u128 {
+    let mut res = 0;
+    for i in 0..n {
+        res |= (encode_declet((x % 1000) as u32) as u128) << (10 * i);
+        x /= 1000;
+    }
+    res
+}
+
+impl IeeeDecimal {
+    // Decodes the low 64 or 128 bits. Non-canonical coefficients, which can
+    // only arise in BID, decode as zero, as do non-canonical NaN payloads.
+    pub fn decode(bits: u128, format: DecimalFormat, encoding: DecimalEncoding) -> Self {
+        let w = format.width();
+        let t = format.trailing_bits();
+        let ew = format.exp_bits();
+        let p = format.precision();
+        let neg = (bits >> (w - 1)) & 1 == 1;
+        let g = (bits >> (w - 6)) & 0x1f;
+        let trailing = bits & mask(t);
+
+        if g == 0b11110 {
+            return IeeeDecimal::Infinity { neg };
+        }
+        if g == 0b11111 {
+            let payload = match encoding {
+                DecimalEncoding::Bid => trailing,
+                DecimalEncoding::Dpd => from_declets(trailing, t / 10),
+            };
+            let payload = if payload < 10u128.pow(p - 1) {
+                payload
+            } else {
+                0
+            };
+            return IeeeDecimal::NaN {
+                neg,
+                signaling: (bits >> (w - 7)) & 1 == 1,
+                payload: BigBcd::from(payload),
+            };
+        }
+
+        let (biased, coeff) = match encoding {
+            DecimalEncoding::Bid if g >> 3 != 0b11 => {
+                ((bits >> (t + 3)) & mask(ew), bits & mask(t + 3))
+            }
+            DecimalEncoding::Bid => (
+                (bits >> (t + 1)) & mask(ew),
+                (0b100 << (t + 1)) | (bits & mask(t + 1)),
+            ),
+            DecimalEncoding::Dpd => {
+                let (top, lead) = if g >> 3 != 0b11 {
+                    (g >> 3, g & 7)
+                } else {
+                    ((g >> 1) & 3, 8 + (g & 1))
+                };
+                let biased = top << (ew - 2) | ((bits >> t) & mask(ew - 2));
+                let coeff = lead * 10u128.pow(p - 1) + from_declets(trailing, t / 10);
+                (biased, coeff)
+            }
+        };
+        let coeff = if coeff < 10u128.pow(p) { coeff } else { 0 };
+        IeeeDecimal::Finite {
+            neg,
+            coeff: BigBcd::from(coeff),
+            exp: biased as i64 - format.bias(),
+        }
+    }
+
+    // The encoding of self in the low 64 or 128 bits, rounding it into the
+    // format first. NaN payloads keep their low precision - 1 digits.
+    pub fn encode(
+        &self,
+        format: DecimalFormat,
+        encoding: DecimalEncoding,
+        mode: RoundingMode,
+    ) -> u128 {
+        let w = format.width();
+        let t = format.trailing_bits();
+        let ew = format.exp_bits();
+        let p = format.precision();
+        match self.rounded(format, mode) {
+            IeeeDecimal::Infinity { neg } => (neg as u128) << (w - 1) | 0b11110 << (w - 6),
+            IeeeDecimal::NaN {
+                neg,
+                signaling,
+                payload,
+            } => {
+                let payload = payload.abs().rem(&BigBcd::pow10(p as usize - 1)).unwrap();
+                let payload = u128::try_from(&payload).unwrap();
+                let trailing = match encoding {
+                    DecimalEncoding::Bid => payload,
+                    DecimalEncoding::Dpd => to_declets(payload, t / 10),
+                };
+                (neg as u128) << (w - 1)
+                    | 0b11111 << (w - 6)
+                    | (signaling as u128) << (w - 7)
+                    | trailing
+            }
+            IeeeDecimal::Finite { neg, coeff, exp } => {
+                let sign = (neg as u128) << (w - 1);
+                let coeff = u128::try_from(&coeff).unwrap();
+                let biased = (exp + format.bias()) as u128;
+                match encoding {
+                    DecimalEncoding::Bid if coeff >> (t + 3) == 0 => {
+                        sign | biased << (t + 3) | coeff
+                    }
+                    DecimalEncoding::Bid => {
+                        sign | 0b11 << (w - 3) | biased << (t + 1) | (coeff & mask(t + 1))
+                    }
+                    DecimalEncoding::Dpd => {
+                        let lead = coeff / 10u128.pow(p - 1);
+                        let top = biased >> (ew - 2);
+                        let g = if lead < 8 {
+                            top << 3 | lead
+                        } else {
+                            0b11000 | top << 1 | (lead - 8)
+                        };
+                        sign | g << (w - 6)
+                            | (biased & mask(ew - 2)) << t
+                            | to_declets(coeff % 10u128.pow(p - 1), t / 10)
+                    }
+                }
+            }
+        }
+    }
+
+    // The value that `encode` stores: finite values are rounded to the
+    // format's precision, or up to its smallest exponent, in one step. Too
+    // large an exponent is clamped by padding the coefficient with zeros if
+    // they fit, and otherwise overflows to infinity or the largest finite
+    // value, as the rounding mode directs.
+    pub fn rounded(&self, format: DecimalFormat, mode: RoundingMode) -> Self {
+        let (neg, coeff, exp) = match self {
+            IeeeDecimal::Finite { neg, coeff, exp } => (*neg, coeff, *exp),
+            _ => return self.clone(),
+        };
+        // In i128, as exp may be anywhere in i64 and the sums below leave it.
+        let exp = exp as i128;
+        let p = format.precision() as i128;
+        let digits = coeff.num_digits() as i128;
+
+        let mut target = exp.max(-format.bias() as i128);
+        if !coeff.is_zero() {
+            target = target.max(exp + digits - p);
+        }
+        let mut coeff = coeff.abs();
+        if target > exp {
+            let k = (target - exp).min(digits + 1) as usize;
+            let signed = if neg { coeff.negated() } else { coeff };
+            coeff = div_round(&signed, &BigBcd::pow10(k), mode).abs();
+            // Rounding up can carry into an extra digit.
+            if coeff.num_digits() as i128 > p {
+                coeff.div_rem_small_assign(10);
+                target += 1;
+            }
+        }
+
+        let mut exp = target;
+        let max_exp = format.max_exp() as i128;
+        if exp > max_exp {
+            if coeff.is_zero() {
+                exp = max_exp;
+            } else if coeff.num_digits() as i128 + (exp - max_exp) <= p {
+                coeff = coeff.shl10((exp - max_exp) as usize);
+                exp = max_exp;
+            } else {
+                let to_infinity = match mode {
+                    RoundingMode::Down => false,
+                    RoundingMode::Ceiling => !neg,
+                    RoundingMode::Floor => neg,
+                    _ => true,
+                };
+                if to_infinity {
+                    return IeeeDecimal::Infinity { neg };
+                }
+                coeff = BigBcd::pow10(p as usize).subtract(&BigBcd::from(1));
+                exp = max_exp;
+            }
+        }
+        // Now between the smallest and largest exponents of the format.
+        let exp = exp as i64;
+        IeeeDecimal::Finite { neg, coeff, exp }
+    }
+
+    // The value of a finite number. -0 becomes 0. None for an exponent of
+    // i64::MIN too, which no BigDecimal scale can negate.
+    pub fn to_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            IeeeDecimal::Finite { neg, coeff, exp } => {
+                let coeff = if *neg { coeff.negated() } else { coeff.clone() };
+                Some(BigDecimal::new(coeff, exp.checked_neg()?))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<&BigDecimal> for IeeeDecimal {
+    fn from(x: &BigDecimal) -> Self {
+        // A scale of i64::MIN has no negation, so one power of ten moves into
+        // the coefficient.
+        let (coeff, exp) = match x.scale().checked_neg() {
+            Some(exp) => (x.coefficient().abs(), exp),
+            None => (x.coefficient().abs().shl10(1), i64::MAX),
+        };
+        IeeeDecimal::Finite {
+            neg: x.is_negative(),
+            coeff,
+            exp,
+        }
+    }
+}
+
+// The to-scientific-string conversion of the General Decimal Arithmetic
+// specification: plain notation when the exponent is at most 0 and the
+// leading digit is at most six places after the point, otherwise one digit
+// before the point and an explicitly signed exponent, as in 1.23E+5.
+impl fmt::Display for IeeeDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let neg = match self {
+            IeeeDecimal::Finite { neg, .. }
+            | IeeeDecimal::Infinity { neg }
+            | IeeeDecimal::NaN { neg, .. } => *neg,
+        };
+        if neg {
+            write!(f, "-")?;
+        }
+        let (coeff, exp) = match self {
+            IeeeDecimal::Finite { coeff, exp, .. } => (coeff, *exp),
+            IeeeDecimal::Infinity { .. } => return write!(f, "Infinity"),
+            IeeeDecimal::NaN {
+                signaling, payload, ..
+            } => {
+                let s = if *signaling { "sNaN" } else { "NaN" };
+                if payload.is_zero() {
+                    return write!(f, "{}", s);
+                }
+                return write!(f, "{}{}", s, payload);
+            }
+        };
+
+        let digits = coeff.to_string();
+        let adjusted = exp as i128 + digits.len() as i128 - 1;
+        if exp <= 0 && adjusted >= -6 {
+            return write!(f, "{}", BigDecimal::new(coeff.clone(), -exp));
+        }
+        write!(f, "{}", &digits[..1])?;
+        if digits.len() > 1 {
+            write!(f, ".{}", &digits[1..])?;
+        }
+        write!(
+            f,
+            "E{}{}",
+            if adjusted < 0 { "-" } else { "+" },
+            adjusted.abs()
+        )
+    }
+}
+
+// Accepts what BigDecimal does, keeping the sign of zero, as well as
+// "Infinity" or "Inf" and "NaN" or "sNaN" with an optional decimal payload,
+// in any case and with an optional sign.
+impl FromStr for IeeeDecimal {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let body = s.trim();
+        let (neg, unsigned) = match body.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, body.strip_prefix('+').unwrap_or(body)),
+        };
+        let unsigned = unsigned.to_ascii_lowercase();
+        if unsigned == "inf" || unsigned == "infinity" {
+            return Ok(IeeeDecimal::Infinity { neg });
+        }
+        for (prefix, signaling) in [("snan", true), ("nan", false)] {
+            let payload = match unsigned.strip_prefix(prefix) {
+                Some(payload) if payload.bytes().all(|b| b.is_ascii_digit()) => payload,
+                _ => continue,
+            };
+            let payload = if payload.is_empty() {
+                BigBcd::from(0)
+            } else {
+                payload.parse()?
+            };
+            return Ok(IeeeDecimal::NaN {
+                neg,
+                signaling,
+                payload,
+            });
+        }
+
+        let x: BigDecimal = body.parse()?;
+        Ok(IeeeDecimal::Finite {
+            neg,
+            coeff: x.coefficient().abs(),
+            exp: -x.scale(),
+        })
+    }
+}
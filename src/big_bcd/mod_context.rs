@@ -0,0 +1,350 @@
+// Contexts for repeated arithmetic modulo one fixed modulus m of n limbs.
+//
+// `ModContext` precomputes the constants for Montgomery reduction when m is
+// coprime to 10, and so to BASE, and for Barrett reduction otherwise. Values
+// are kept in residue form between operations: x R mod m with R = BASE^n for
+// Montgomery, and plain x mod m for Barrett. Every multiplication then
+// reduces with two multiplications instead of a division.
+//
+// `ConstTimeModContext` is a Montgomery context whose multiplication,
+// squaring and exponentiation do the same work, with no branches or memory
+// accesses that depend on the residues, whatever their values. It is meant
+// for teaching side-channel-aware code rather than for speed. Converting to
+// and from residues, and reading the exponent, are not constant-time.
+
+use super::div::{from_limbs, reciprocal, shl, shr};
+use super::radix::to_chunks;
+use super::{BigBcd, DivisionByZero, BASE};
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug)]
+pub struct ModContext {
+    modulus: BigBcd,
+    reduction: Reduction,
+}
+
+#[derive(Clone, Debug)]
+enum Reduction {
+    // -m^-1 mod BASE, and R^2 mod m.
+    Montgomery { m_inv: u32, r2: BigBcd },
+    // floor(BASE^2n / m).
+    Barrett { mu: BigBcd },
+}
+
+impl ModContext {
+    // A context for |modulus|.
+    pub fn new(modulus: &BigBcd) -> Result<Self, DivisionByZero> {
+        if modulus.is_zero() {
+            return Err(DivisionByZero);
+        }
+        let modulus = modulus.abs();
+        let n = modulus.limbs.len();
+        let reduction = if coprime_to_base(&modulus) {
+            Reduction::Montgomery {
+                m_inv: neg_inverse(modulus.limbs[0]),
+                r2: shl(&BigBcd::from(1), 2 * n).rem(&modulus).unwrap(),
+            }
+        } else {
+            Reduction::Barrett {
+                mu: reciprocal(&modulus),
+            }
+        };
+        Ok(ModContext { modulus, reduction })
+    }
+
+    pub fn modulus(&self) -> &BigBcd {
+        &self.modulus
+    }
+
+    pub fn to_residue(&self, x: &BigBcd) -> BigBcd {
+        let x = x.rem_euclid(&self.modulus).unwrap();
+        match &self.reduction {
+            Reduction::Montgomery { r2, .. } => self.mul_mod(&x, r2),
+            Reduction::Barrett { .. } => x,
+        }
+    }
+
+    pub fn from_residue(&self, x: &BigBcd) -> BigBcd {
+        match &self.reduction {
+            Reduction::Montgomery { m_inv, .. } => redc(&x.limbs, &self.modulus, *m_inv),
+            Reduction::Barrett { .. } => x.clone(),
+        }
+    }
+
+    // The product of two residues, as a residue.
+    pub fn mul_mod(&self, a: &BigBcd, b: &BigBcd) -> BigBcd {
+        self.reduce(&a.multiply(b))
+    }
+
+    pub fn sqr_mod(&self, a: &BigBcd) -> BigBcd {
+        self.reduce(&a.multiply(a))
+    }
+
+    // a^exp for a residue a and exp >= 0, as a residue, by sliding windows
+    // over the bits of exp using the precomputed odd powers a, a^3, ...,
+    // a^(2^w - 1).
+    pub fn pow_mod(&self, a: &BigBcd, exp: &BigBcd) -> BigBcd {
+        assert!(!exp.neg, "negative exponent");
+        let words = to_words(exp);
+        let bits = words.len() * 32;
+        let bit = |i: usize| (words[i / 32] >> (i % 32)) & 1 == 1;
+        let w = window_bits(bits);
+
+        let square = self.sqr_mod(a);
+        let mut odd_powers = vec![a.clone()];
+        for i in 1..1 << (w - 1) {
+            odd_powers.push(self.mul_mod(&odd_powers[i - 1], &square));
+        }
+
+        let mut res = self.to_residue(&BigBcd::from(1));
+        let mut i = bits;
+        while i > 0 {
+            if !bit(i - 1) {
+                res = self.sqr_mod(&res);
+                i -= 1;
+                continue;
+            }
+            // The longest window ending in a set bit, at most w bits long.
+            let mut lo = i.saturating_sub(w);
+            while !bit(lo) {
+                lo += 1;
+            }
+            let mut value = 0;
+            for j in (lo..i).rev() {
+                res = self.sqr_mod(&res);
+                value = (value << 1) | bit(j) as usize;
+            }
+            res = self.mul_mod(&res, &odd_powers[value >> 1]);
+            i = lo;
+        }
+        res
+    }
+
+    // Reduces a product of two residues.
+    fn reduce(&self, x: &BigBcd) -> BigBcd {
+        match &self.reduction {
+            Reduction::Montgomery { m_inv, .. } => redc(&x.limbs, &self.modulus, *m_inv),
+            Reduction::Barrett { mu } => {
+                // HAC 14.42: the estimate is at most two below x / m.
+                let n = self.modulus.limbs.len();
+                let q = shr(&shr(x, n - 1).multiply(mu), n + 1);
+                let mut r = x.subtract(&q.multiply(&self.modulus));
+                while r.cmp_mag(&self.modulus) != Ordering::Less {
+                    r.sub_mag_assign(&self.modulus);
+                }
+                r
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstTimeModContext {
+    // The modulus, -m^-1 mod BASE, R mod m and R^2 mod m, all n limbs long.
+    modulus: Vec<u32>,
+    m_inv: u32,
+    one: Vec<u32>,
+    r2: Vec<u32>,
+}
+
+impl ConstTimeModContext {
+    // A context for |modulus|, which must be coprime to 10.
+    pub fn new(modulus: &BigBcd) -> Option<Self> {
+        if modulus.is_zero() || !coprime_to_base(modulus) {
+            return None;
+        }
+        let m = modulus.abs();
+        let n = m.limbs.len();
+        let pad = |x: BigBcd| {
+            let mut limbs = x.limbs;
+            limbs.resize(n, 0);
+            limbs
+        };
+        Some(ConstTimeModContext {
+            m_inv: neg_inverse(m.limbs[0]),
+            one: pad(shl(&BigBcd::from(1), n).rem(&m).unwrap()),
+            r2: pad(shl(&BigBcd::from(1), 2 * n).rem(&m).unwrap()),
+            modulus: m.limbs,
+        })
+    }
+
+    pub fn modulus(&self) -> BigBcd {
+        from_limbs(self.modulus.clone())
+    }
+
+    pub fn to_residue(&self, x: &BigBcd) -> BigBcd {
+        let x = self.pad(&x.rem_euclid(&self.modulus()).unwrap());
+        from_limbs(self.mont_mul(&x, &self.r2))
+    }
+
+    pub fn from_residue(&self, x: &BigBcd) -> BigBcd {
+        let mut one = vec![0; self.modulus.len()];
+        one[0] = 1;
+        from_limbs(self.mont_mul(&self.pad(x), &one))
+    }
+
+    pub fn mul_mod(&self, a: &BigBcd, b: &BigBcd) -> BigBcd {
+        from_limbs(self.mont_mul(&self.pad(a), &self.pad(b)))
+    }
+
+    pub fn sqr_mod(&self, a: &BigBcd) -> BigBcd {
+        let a = self.pad(a);
+        from_limbs(self.mont_mul(&a, &a))
+    }
+
+    // a^exp for a residue a and exp >= 0, as a residue, by a Montgomery
+    // ladder: one multiplication and one squaring for every bit, over at
+    // least as many bits as the modulus has.
+    pub fn pow_mod(&self, a: &BigBcd, exp: &BigBcd) -> BigBcd {
+        assert!(!exp.neg, "negative exponent");
+        let mut words = to_words(exp);
+        let min_words = to_words(&self.modulus()).len();
+        if words.len() < min_words {
+            words.resize(min_words, 0);
+        }
+
+        let (mut r0, mut r1) = (self.one.clone(), self.pad(a));
+        for i in (0..words.len() * 32).rev() {
+            let bit = (words[i / 32] >> (i % 32)) & 1;
+            cswap(&mut r0, &mut r1, bit);
+            r1 = self.mont_mul(&r0, &r1);
+            r0 = self.mont_mul(&r0, &r0);
+            cswap(&mut r0, &mut r1, bit);
+        }
+        from_limbs(r0)
+    }
+
+    fn pad(&self, x: &BigBcd) -> Vec<u32> {
+        let mut limbs = x.limbs.clone();
+        limbs.resize(self.modulus.len(), 0);
+        limbs
+    }
+
+    // a * b / R mod m for a, b < m, by interleaved (CIOS) Montgomery
+    // multiplication with a masked final subtraction.
+    fn mont_mul(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let base = BASE as u64;
+        let m = &self.modulus;
+        let n = m.len();
+
+        // t < 2m throughout, held in n + 2 limbs.
+        let mut t = vec![0u64; n + 2];
+        for &x in a {
+            let mut carry = 0;
+            for (dst, &y) in t.iter_mut().zip(b) {
+                let curr = *dst + x as u64 * y as u64 + carry;
+                *dst = curr % base;
+                carry = curr / base;
+            }
+            let curr = t[n] + carry;
+            t[n] = curr % base;
+            t[n + 1] = curr / base;
+
+            // t = (t + u * m) / BASE, where u makes the low limb vanish.
+            let u = t[0] * self.m_inv as u64 % base;
+            let mut carry = (t[0] + u * m[0] as u64) / base;
+            for j in 1..n {
+                let curr = t[j] + u * m[j] as u64 + carry;
+                t[j - 1] = curr % base;
+                carry = curr / base;
+            }
+            let curr = t[n] + carry;
+            t[n - 1] = curr % base;
+            t[n] = t[n + 1] + curr / base;
+        }
+
+        // Subtract m, and keep t instead if that borrowed past t[n].
+        let mut diff = vec![0; n];
+        let mut borrow = 0;
+        for ((dst, &x), &y) in diff.iter_mut().zip(t.iter()).zip(m) {
+            let curr = x as i64 - y as i64 - borrow;
+            borrow = (curr < 0) as i64;
+            *dst = (curr + borrow * base as i64) as u32;
+        }
+        let keep_t = (borrow as u32) & (1 ^ t[n] as u32);
+        let mask = 0u32.wrapping_sub(keep_t);
+        t.iter()
+            .zip(diff.iter())
+            .map(|(&x, &y)| (x as u32 & mask) | (y & !mask))
+            .collect()
+    }
+}
+
+// Swaps a and b if bit is 1, without branching on it.
+fn cswap(a: &mut [u32], b: &mut [u32], bit: u32) {
+    let mask = 0u32.wrapping_sub(bit);
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let d = (*x ^ *y) & mask;
+        *x ^= d;
+        *y ^= d;
+    }
+}
+
+fn coprime_to_base(m: &BigBcd) -> bool {
+    !m.limbs[0].is_multiple_of(2) && !m.limbs[0].is_multiple_of(5)
+}
+
+// -x^-1 mod BASE, for x coprime to BASE, by the extended Euclidean algorithm.
+fn neg_inverse(x: u32) -> u32 {
+    let base = BASE as i64;
+    let (mut r0, mut r1) = (base, x as i64 % base);
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        let r = r0 - q * r1;
+        r0 = r1;
+        r1 = r;
+        let t = t0 - q * t1;
+        t0 = t1;
+        t1 = t;
+    }
+    ((base - t0.rem_euclid(base)) % base) as u32
+}
+
+// x / R mod m for x < m R, by Montgomery's REDC.
+fn redc(x: &[u32], modulus: &BigBcd, m_inv: u32) -> BigBcd {
+    let base = BASE as u64;
+    let m = &modulus.limbs;
+    let n = m.len();
+    let mut t = x.to_vec();
+    t.resize(2 * n + 1, 0);
+    for i in 0..n {
+        let u = t[i] as u64 * m_inv as u64 % base;
+        let mut carry = 0;
+        for (dst, &y) in t[i..i + n].iter_mut().zip(m) {
+            let curr = *dst as u64 + u * y as u64 + carry;
+            *dst = (curr % base) as u32;
+            carry = curr / base;
+        }
+        for dst in t[i + n..].iter_mut() {
+            if carry == 0 {
+                break;
+            }
+            let curr = *dst as u64 + carry;
+            *dst = (curr % base) as u32;
+            carry = curr / base;
+        }
+    }
+    let mut res = from_limbs(t.split_off(n));
+    if res.cmp_mag(modulus) != Ordering::Less {
+        res.sub_mag_assign(modulus);
+    }
+    res
+}
+
+// Splits |x| into base 2^32 words, least significant first.
+fn to_words(x: &BigBcd) -> Vec<u32> {
+    to_chunks(x, 1 << 32).iter().map(|&w| w as u32).collect()
+}
+
+// Window width for sliding-window exponentiation with an exponent of `bits`
+// bits, trading precomputed odd powers against multiplications saved.
+fn window_bits(bits: usize) -> usize {
+    match bits {
+        0..=24 => 1,
+        25..=80 => 3,
+        81..=240 => 4,
+        241..=672 => 5,
+        _ => 6,
+    }
+}
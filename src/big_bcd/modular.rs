@@ -0,0 +1,131 @@
+// Powers, modular exponentiation, GCDs and modular inverses.
+
+use super::{BigBcd, ModContext};
+use std::mem;
+
+impl BigBcd {
+    // self^exp by square-and-multiply.
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut res = BigBcd::from(1);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res *= &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.multiply(&base);
+            }
+        }
+        res
+    }
+
+    // self^exp mod |modulus|, in [0, |modulus|). A negative exponent raises
+    // the modular inverse instead. None if the modulus is zero, or if the
+    // exponent is negative and self has no inverse.
+    pub fn mod_pow(&self, exp: &Self, modulus: &Self) -> Option<Self> {
+        let ctx = ModContext::new(modulus).ok()?;
+        let base = if exp.neg {
+            self.mod_inverse(modulus)?
+        } else {
+            self.clone()
+        };
+        let res = ctx.pow_mod(&ctx.to_residue(&base), &exp.abs());
+        Some(ctx.from_residue(&res))
+    }
+
+    // Euclid's algorithm. The result is non-negative.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        while !b.is_zero() {
+            let r = a.rem(&b).unwrap();
+            a = mem::replace(&mut b, r);
+        }
+        a
+    }
+
+    // Stein's binary GCD, which only needs halving and subtraction.
+    pub fn gcd_binary(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        if a.is_zero() {
+            return b;
+        }
+        if b.is_zero() {
+            return a;
+        }
+
+        let shift = a.strip_twos().min(b.strip_twos());
+        loop {
+            // Both are odd here.
+            if a < b {
+                mem::swap(&mut a, &mut b);
+            }
+            a.sub_mag_assign(&b);
+            if a.is_zero() {
+                break;
+            }
+            a.strip_twos();
+        }
+        for _ in 0..shift {
+            b.mul_small_assign(2);
+        }
+        b
+    }
+
+    // Divides |self| by its largest power-of-two factor, returning the
+    // exponent. BASE is a multiple of 2^9, so the lowest limb shows up to
+    // nine factors of two at a time.
+    fn strip_twos(&mut self) -> u32 {
+        let mut count = 0;
+        while !self.is_zero() {
+            let twos = self.limbs[0].trailing_zeros().min(9);
+            if twos == 0 {
+                break;
+            }
+            self.div_rem_small_assign(1 << twos);
+            count += twos;
+        }
+        count
+    }
+
+    // The least common multiple, non-negative. Zero if either is zero.
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return BigBcd::from(0);
+        }
+        self.divide(&self.gcd(other)).unwrap().multiply(other).abs()
+    }
+
+    // (g, x, y) with g = gcd(self, other) and self * x + other * y == g.
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (BigBcd::from(1), BigBcd::from(0));
+        let (mut t0, mut t1) = (BigBcd::from(0), BigBcd::from(1));
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1).unwrap();
+            r0 = mem::replace(&mut r1, r);
+            let s = s0.subtract(&q.multiply(&s1));
+            s0 = mem::replace(&mut s1, s);
+            let t = t0.subtract(&q.multiply(&t1));
+            t0 = mem::replace(&mut t1, t);
+        }
+        if r0.neg {
+            (r0.negated(), s0.negated(), t0.negated())
+        } else {
+            (r0, s0, t0)
+        }
+    }
+
+    // x in [0, |modulus|) with self * x == 1 mod modulus, if self and
+    // modulus are coprime.
+    pub fn mod_inverse(&self, modulus: &Self) -> Option<Self> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let (g, x, _) = self.extended_gcd(modulus);
+        if g != BigBcd::from(1) {
+            return None;
+        }
+        Some(x.rem_euclid(modulus).unwrap())
+    }
+}
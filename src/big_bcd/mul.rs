@@ -0,0 +1,210 @@
+// Multiplication of limb slices (least significant first). The results may
+// have leading zeros; callers trim them.
+//
+// Small operands use schoolbook multiplication, medium ones Karatsuba and large
+// ones the number-theoretic transform in `ntt`. Toom-3 takes over from the
+// transform for operands too long for its primes. Operands of very different
+// lengths are cut into pieces the size of the shorter one first, so the
+// recursive algorithms always see roughly balanced inputs.
+
+use super::{ntt, parallel, BigBcd, BASE};
+
+// Lengths of the shorter operand, in limbs, at which the faster algorithms
+// start paying for their overhead.
+const KARATSUBA_THRESHOLD: usize = 32;
+const TOOM3_THRESHOLD: usize = 128;
+const NTT_THRESHOLD: usize = 1500;
+
+pub(super) fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = (trim_slice(a), trim_slice(b));
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if b.len() < KARATSUBA_THRESHOLD {
+        schoolbook(a, b)
+    } else if b.len() >= NTT_THRESHOLD && ntt::fits(a.len(), b.len()) {
+        ntt::mul(a, b)
+    } else if b.len() * 2 <= a.len() {
+        unbalanced(a, b)
+    } else if b.len() < TOOM3_THRESHOLD {
+        karatsuba(a, b)
+    } else {
+        toom3(a, b)
+    }
+}
+
+pub(super) fn schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0 {
+            continue;
+        }
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let curr = res[i + j] as u64 + (x as u64) * (y as u64) + carry;
+            res[i + j] = (curr % BASE as u64) as u32;
+            carry = curr / BASE as u64;
+        }
+        res[i + b.len()] = carry as u32;
+    }
+    res
+}
+
+// Multiplies a long a by a much shorter b, one b-sized chunk of a at a time.
+fn unbalanced(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let chunks: Vec<&[u32]> = a.chunks(b.len()).collect();
+    let products = parallel::map(b.len(), &chunks, &|chunk| mul_limbs(chunk, b));
+    let mut res = vec![0; a.len() + b.len()];
+    for (i, product) in products.iter().enumerate() {
+        add_at(&mut res, product, i * b.len());
+    }
+    res
+}
+
+// a * b = z2 * BASE^2m + z1 * BASE^m + z0 where
+// z2 = a1 * b1, z0 = a0 * b0 and z1 = (a0 + a1) * (b0 + b1) - z2 - z0.
+pub(super) fn karatsuba(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let m = a.len().max(b.len()).div_ceil(2);
+    let (a0, a1) = split(a, m);
+    let (b0, b1) = split(b, m);
+
+    let z0 = mul_limbs(a0, b0);
+    let z2 = mul_limbs(a1, b1);
+    let mut z1 = mul_limbs(&add_slices(a0, a1), &add_slices(b0, b1));
+    sub_assign(&mut z1, &z0);
+    sub_assign(&mut z1, &z2);
+
+    let mut res = vec![0; a.len() + b.len() + 1];
+    add_at(&mut res, &z0, 0);
+    add_at(&mut res, &z1, m);
+    add_at(&mut res, &z2, 2 * m);
+    res
+}
+
+// Toom-3 with Bodrato's evaluation points 0, 1, -1, -2 and infinity. The
+// intermediate values can be negative, so they are kept as signed BigBcds.
+pub(super) fn toom3(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let k = a.len().max(b.len()).div_ceil(3);
+
+    let p = evaluate(a, k);
+    let q = evaluate(b, k);
+    let pairs: Vec<(&BigBcd, &BigBcd)> = p.iter().zip(q.iter()).collect();
+    let r = parallel::map(k, &pairs, &|&(x, y)| mul_signed(x, y));
+
+    // r = [r(0), r(1), r(-1), r(-2), r(inf)]
+    let (r0, r4) = (&r[0], &r[4]);
+    let mut r3 = r[3].subtract(&r[1]);
+    r3.div_rem_small_assign(3);
+    let mut r1 = r[1].subtract(&r[2]);
+    r1.div_rem_small_assign(2);
+    let mut r2 = r[2].subtract(r0);
+    r3 = r2.subtract(&r3);
+    r3.div_rem_small_assign(2);
+    let mut twice_r4 = r4.clone();
+    twice_r4.mul_small_assign(2);
+    r3.add_signed_assign(&twice_r4, false);
+    r2.add_signed_assign(&r1, r1.neg);
+    r2.add_signed_assign(r4, true);
+    r1.add_signed_assign(&r3, !r3.neg);
+
+    let mut res = vec![0; a.len() + b.len() + 1];
+    for (i, coeff) in [r0, &r1, &r2, &r3, r4].iter().enumerate() {
+        debug_assert!(!coeff.neg);
+        add_at(&mut res, &coeff.limbs, i * k);
+    }
+    res
+}
+
+// Values of the polynomial m2 x^2 + m1 x + m0 (the k-limb pieces of x) at
+// 0, 1, -1, -2 and infinity.
+fn evaluate(x: &[u32], k: usize) -> [BigBcd; 5] {
+    let (m0, rest) = split(x, k);
+    let (m1, m2) = split(rest, k);
+    let (m0, m1, m2) = (from_slice(m0), from_slice(m1), from_slice(m2));
+
+    let p0 = m0.add(&m2);
+    let at_1 = p0.add(&m1);
+    let at_neg_1 = p0.subtract(&m1);
+    let mut at_neg_2 = at_neg_1.add(&m2);
+    at_neg_2.mul_small_assign(2);
+    at_neg_2.add_signed_assign(&m0, true);
+
+    [m0, at_1, at_neg_1, at_neg_2, m2]
+}
+
+fn mul_signed(x: &BigBcd, y: &BigBcd) -> BigBcd {
+    let mut res = BigBcd {
+        limbs: mul_limbs(&x.limbs, &y.limbs),
+        neg: x.neg != y.neg,
+    };
+    res.trim();
+    res
+}
+
+fn from_slice(x: &[u32]) -> BigBcd {
+    let mut res = BigBcd {
+        limbs: x.to_vec(),
+        neg: false,
+    };
+    res.trim();
+    res
+}
+
+fn trim_slice(x: &[u32]) -> &[u32] {
+    let mut len = x.len();
+    while len > 0 && x[len - 1] == 0 {
+        len -= 1;
+    }
+    &x[..len]
+}
+
+// Splits x into its low m limbs and the rest.
+fn split(x: &[u32], m: usize) -> (&[u32], &[u32]) {
+    x.split_at(m.min(x.len()))
+}
+
+fn add_slices(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = vec![0; a.len().max(b.len()) + 1];
+    add_at(&mut res, a, 0);
+    add_at(&mut res, b, 0);
+    res
+}
+
+// acc += x * BASE^offset. acc must be long enough to hold the result.
+fn add_at(acc: &mut [u32], x: &[u32], offset: usize) {
+    let x = trim_slice(x);
+    let mut carry = 0;
+    let mut i = offset;
+    for &limb in x {
+        let curr = acc[i] + limb + carry;
+        carry = (curr >= BASE) as u32;
+        acc[i] = curr - carry * BASE;
+        i += 1;
+    }
+    while carry > 0 {
+        let curr = acc[i] + carry;
+        carry = (curr >= BASE) as u32;
+        acc[i] = curr - carry * BASE;
+        i += 1;
+    }
+}
+
+// acc -= x. Requires acc >= x.
+fn sub_assign(acc: &mut [u32], x: &[u32]) {
+    let x = trim_slice(x);
+    let mut borrow = 0;
+    let mut i = 0;
+    for &limb in x {
+        let mut curr = acc[i] as i64 - limb as i64 - borrow;
+        borrow = (curr < 0) as i64;
+        curr += borrow * BASE as i64;
+        acc[i] = curr as u32;
+        i += 1;
+    }
+    while borrow > 0 {
+        let mut curr = acc[i] as i64 - borrow;
+        borrow = (curr < 0) as i64;
+        curr += borrow * BASE as i64;
+        acc[i] = curr as u32;
+        i += 1;
+    }
+}
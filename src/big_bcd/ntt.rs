@@ -0,0 +1,148 @@
+// Multiplication by number-theoretic transform, for operands far beyond where
+// Toom-3 stops being competitive.
+//
+// Each limb is one coefficient. The convolution is computed modulo three
+// NTT-friendly primes and the exact coefficients are rebuilt with the Chinese
+// remainder theorem. Each coefficient of the product is below
+// len * (10^9)^2 < 2^23 * 10^18, which is well under the product of the primes
+// (about 7.9 * 10^25), so the result is exact.
+
+use super::{parallel, BASE};
+
+// (prime, primitive root). The first is 119 * 2^23 + 1, which limits the
+// transform length to 2^23.
+const PRIMES: [(u64, u64); 3] = [(998244353, 3), (167772161, 3), (469762049, 3)];
+const MAX_LEN: usize = 1 << 23;
+
+// Whether operands of these lengths (in limbs) are small enough for `mul`.
+pub(super) fn fits(a_len: usize, b_len: usize) -> bool {
+    (a_len + b_len).next_power_of_two() <= MAX_LEN
+}
+
+pub(super) fn mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let len = (a.len() + b.len()).next_power_of_two();
+    assert!(len <= MAX_LEN, "operands too large for NTT multiplication");
+
+    let [(p1, g1), (p2, g2), (p3, g3)] = PRIMES;
+    // The three transforms are independent.
+    let (c1, (c2, c3)) = parallel::join(
+        b.len(),
+        || convolve(a, b, len, p1, g1),
+        || {
+            parallel::join(
+                b.len(),
+                || convolve(a, b, len, p2, g2),
+                || convolve(a, b, len, p3, g3),
+            )
+        },
+    );
+
+    let p1_inv_p2 = pow_mod(p1 % p2, p2 - 2, p2);
+    let p12_inv_p3 = pow_mod((p1 * p2) % p3, p3 - 2, p3);
+
+    let mut res = Vec::with_capacity(a.len() + b.len() + 1);
+    let mut carry: u128 = 0;
+    let residues = c1.iter().zip(c2.iter()).zip(c3.iter());
+    for ((&r1, &r2), &r3) in residues.take(a.len() + b.len()) {
+        // Garner's algorithm.
+        let t = ((r2 + p2 - r1 % p2) % p2) * p1_inv_p2 % p2;
+        let x12 = r1 + p1 * t;
+        let t = ((r3 + p3 - x12 % p3) % p3) * p12_inv_p3 % p3;
+        let x = x12 as u128 + (p1 as u128) * (p2 as u128) * (t as u128);
+
+        let curr = x + carry;
+        res.push((curr % BASE as u128) as u32);
+        carry = curr / BASE as u128;
+    }
+    while carry > 0 {
+        res.push((carry % BASE as u128) as u32);
+        carry /= BASE as u128;
+    }
+    res
+}
+
+// Cyclic convolution of a and b of length len, modulo p.
+fn convolve(a: &[u32], b: &[u32], len: usize, p: u64, g: u64) -> Vec<u64> {
+    let mut fa = vec![0; len];
+    let mut fb = vec![0; len];
+    for (dst, &x) in fa.iter_mut().zip(a.iter()) {
+        *dst = x as u64 % p;
+    }
+    for (dst, &x) in fb.iter_mut().zip(b.iter()) {
+        *dst = x as u64 % p;
+    }
+
+    transform(&mut fa, p, g, false);
+    transform(&mut fb, p, g, false);
+    for (x, y) in fa.iter_mut().zip(fb.iter()) {
+        *x = *x * y % p;
+    }
+    transform(&mut fa, p, g, true);
+
+    let len_inv = pow_mod(len as u64, p - 2, p);
+    for x in fa.iter_mut() {
+        *x = *x * len_inv % p;
+    }
+    fa
+}
+
+// In-place iterative Cooley-Tukey transform. The inverse is left unscaled.
+fn transform(a: &mut [u64], p: u64, g: u64, inverse: bool) {
+    let n = a.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let mut w_len = pow_mod(g, (p - 1) / len as u64, p);
+        if inverse {
+            w_len = pow_mod(w_len, p - 2, p);
+        }
+        let half = len / 2;
+        let mut roots = Vec::with_capacity(half);
+        let mut w = 1;
+        for _ in 0..half {
+            roots.push(w);
+            w = w * w_len % p;
+        }
+
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(half);
+            for ((x, y), &w) in lo.iter_mut().zip(hi.iter_mut()).zip(roots.iter()) {
+                let u = *x;
+                let v = *y * w % p;
+                *x = if u + v >= p { u + v - p } else { u + v };
+                *y = if u >= v { u - v } else { u + p - v };
+            }
+        }
+        len <<= 1;
+    }
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut res = 1;
+    base %= p;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = res * base % p;
+        }
+        base = base * base % p;
+        exp >>= 1;
+    }
+    res
+}
@@ -0,0 +1,168 @@
+// num-traits impls for BigBcd, so it can be used with generic numeric code
+// such as `ratio::Ratio`. There is deliberately no `Bounded`.
+
+use super::{BigBcd, ParseError};
+use num_traits::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, FromPrimitive, Num, One, Pow,
+    Signed, ToPrimitive, Zero,
+};
+use std::convert::TryFrom;
+
+impl Zero for BigBcd {
+    fn zero() -> Self {
+        BigBcd::from(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        BigBcd::is_zero(self)
+    }
+}
+
+impl One for BigBcd {
+    fn one() -> Self {
+        BigBcd::from(1)
+    }
+}
+
+impl Num for BigBcd {
+    type FromStrRadixErr = ParseError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        BigBcd::from_str_radix(s, radix)
+    }
+}
+
+impl Signed for BigBcd {
+    fn abs(&self) -> Self {
+        BigBcd::abs(self)
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other {
+            BigBcd::from(0)
+        } else {
+            self.subtract(other)
+        }
+    }
+
+    fn signum(&self) -> Self {
+        if self.neg {
+            BigBcd::from_mag(1, true)
+        } else if BigBcd::is_zero(self) {
+            BigBcd::from(0)
+        } else {
+            BigBcd::from(1)
+        }
+    }
+
+    fn is_positive(&self) -> bool {
+        BigBcd::is_positive(self)
+    }
+
+    fn is_negative(&self) -> bool {
+        BigBcd::is_negative(self)
+    }
+}
+
+impl Pow<u32> for &BigBcd {
+    type Output = BigBcd;
+
+    fn pow(self, exp: u32) -> BigBcd {
+        BigBcd::pow(self, exp)
+    }
+}
+
+impl Pow<u32> for BigBcd {
+    type Output = BigBcd;
+
+    fn pow(self, exp: u32) -> BigBcd {
+        BigBcd::pow(&self, exp)
+    }
+}
+
+impl CheckedAdd for BigBcd {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self.add(other))
+    }
+}
+
+impl CheckedSub for BigBcd {
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Some(self.subtract(other))
+    }
+}
+
+impl CheckedMul for BigBcd {
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self.multiply(other))
+    }
+}
+
+impl CheckedDiv for BigBcd {
+    fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.divide(other).ok()
+    }
+}
+
+impl CheckedRem for BigBcd {
+    fn checked_rem(&self, other: &Self) -> Option<Self> {
+        BigBcd::rem(self, other).ok()
+    }
+}
+
+impl FromPrimitive for BigBcd {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(BigBcd::from(n))
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(BigBcd::from(n))
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        Some(BigBcd::from(n))
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(BigBcd::from(n))
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        BigBcd::from_f64(n)
+    }
+}
+
+impl ToPrimitive for BigBcd {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_i128().and_then(|x| x.to_i64())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|x| x.to_u64())
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        i128::try_from(self).ok()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        u128::try_from(self).ok()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(BigBcd::to_f64(self))
+    }
+}
+
+// Not imported, as its `from` would clash with `From::from` in this file.
+impl num_traits::NumCast for BigBcd {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        match n.to_i128() {
+            Some(x) => BigBcd::from_i128(x),
+            None => match n.to_u128() {
+                Some(x) => BigBcd::from_u128(x),
+                None => n.to_f64().and_then(BigBcd::from_f64),
+            },
+        }
+    }
+}
@@ -0,0 +1,392 @@
+// std::ops impls for BigBcd. The `*Assign` forms do the real work for
+// addition and subtraction so that accumulating loops reuse `limbs`; every other
+// owned/borrowed combination forwards to them.
+
+use super::convert::Primitive;
+use super::BigBcd;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+impl AddAssign<&BigBcd> for BigBcd {
+    fn add_assign(&mut self, other: &BigBcd) {
+        self.add_signed_assign(other, other.neg);
+    }
+}
+
+impl SubAssign<&BigBcd> for BigBcd {
+    fn sub_assign(&mut self, other: &BigBcd) {
+        self.add_signed_assign(other, !other.neg);
+    }
+}
+
+impl MulAssign<&BigBcd> for BigBcd {
+    fn mul_assign(&mut self, other: &BigBcd) {
+        *self = self.multiply(other);
+    }
+}
+
+// Like the primitive operators, `/` and `%` panic on division by zero; the
+// methods return an error instead.
+impl DivAssign<&BigBcd> for BigBcd {
+    fn div_assign(&mut self, other: &BigBcd) {
+        *self = self.divide(other).expect("attempt to divide by zero");
+    }
+}
+
+impl RemAssign<&BigBcd> for BigBcd {
+    fn rem_assign(&mut self, other: &BigBcd) {
+        *self = BigBcd::rem(self, other)
+            .expect("attempt to calculate the remainder with a divisor of zero");
+    }
+}
+
+impl Add<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn add(self, other: &BigBcd) -> BigBcd {
+        BigBcd::add(self, other)
+    }
+}
+
+impl Sub<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn sub(self, other: &BigBcd) -> BigBcd {
+        self.subtract(other)
+    }
+}
+
+impl Mul<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn mul(self, other: &BigBcd) -> BigBcd {
+        self.multiply(other)
+    }
+}
+
+impl Div<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn div(self, other: &BigBcd) -> BigBcd {
+        self.divide(other).expect("attempt to divide by zero")
+    }
+}
+
+impl Rem<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn rem(self, other: &BigBcd) -> BigBcd {
+        BigBcd::rem(self, other).expect("attempt to calculate the remainder with a divisor of zero")
+    }
+}
+
+// The bitwise operators act on the infinite two's complement, as in Python.
+impl BitAndAssign<&BigBcd> for BigBcd {
+    fn bitand_assign(&mut self, other: &BigBcd) {
+        *self = self.bitwise(other, |a, b| a & b);
+    }
+}
+
+impl BitOrAssign<&BigBcd> for BigBcd {
+    fn bitor_assign(&mut self, other: &BigBcd) {
+        *self = self.bitwise(other, |a, b| a | b);
+    }
+}
+
+impl BitXorAssign<&BigBcd> for BigBcd {
+    fn bitxor_assign(&mut self, other: &BigBcd) {
+        *self = self.bitwise(other, |a, b| a ^ b);
+    }
+}
+
+impl BitAnd<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn bitand(self, other: &BigBcd) -> BigBcd {
+        self.bitwise(other, |a, b| a & b)
+    }
+}
+
+impl BitOr<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn bitor(self, other: &BigBcd) -> BigBcd {
+        self.bitwise(other, |a, b| a | b)
+    }
+}
+
+impl BitXor<&BigBcd> for &BigBcd {
+    type Output = BigBcd;
+
+    fn bitxor(self, other: &BigBcd) -> BigBcd {
+        self.bitwise(other, |a, b| a ^ b)
+    }
+}
+
+// Fills in the owned variants of a binary operator from the `&a op &b` and
+// `a op= &b` impls above.
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident) => {
+        impl $imp<BigBcd> for BigBcd {
+            type Output = BigBcd;
+
+            fn $method(mut self, other: BigBcd) -> BigBcd {
+                self.$assign_method(&other);
+                self
+            }
+        }
+
+        impl $imp<&BigBcd> for BigBcd {
+            type Output = BigBcd;
+
+            fn $method(mut self, other: &BigBcd) -> BigBcd {
+                self.$assign_method(other);
+                self
+            }
+        }
+
+        impl $imp<BigBcd> for &BigBcd {
+            type Output = BigBcd;
+
+            fn $method(self, other: BigBcd) -> BigBcd {
+                $imp::$method(self, &other)
+            }
+        }
+
+        impl $assign_imp<BigBcd> for BigBcd {
+            fn $assign_method(&mut self, other: BigBcd) {
+                self.$assign_method(&other);
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, AddAssign, add_assign);
+forward_binop!(Sub, sub, SubAssign, sub_assign);
+forward_binop!(Mul, mul, MulAssign, mul_assign);
+forward_binop!(Div, div, DivAssign, div_assign);
+forward_binop!(Rem, rem, RemAssign, rem_assign);
+forward_binop!(BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_binop!(BitOr, bitor, BitOrAssign, bitor_assign);
+forward_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Neg for BigBcd {
+    type Output = BigBcd;
+
+    fn neg(mut self) -> BigBcd {
+        self.neg = !self.neg;
+        self.fix_sign();
+        self
+    }
+}
+
+impl Neg for &BigBcd {
+    type Output = BigBcd;
+
+    fn neg(self) -> BigBcd {
+        self.negated()
+    }
+}
+
+// !x = -x - 1 in two's complement.
+impl Not for BigBcd {
+    type Output = BigBcd;
+
+    fn not(self) -> BigBcd {
+        -self - 1
+    }
+}
+
+impl Not for &BigBcd {
+    type Output = BigBcd;
+
+    fn not(self) -> BigBcd {
+        !self.clone()
+    }
+}
+
+// Shifts multiply or divide by powers of two, with `>>` rounding towards
+// negative infinity like an arithmetic shift.
+impl Shl<usize> for &BigBcd {
+    type Output = BigBcd;
+
+    fn shl(self, k: usize) -> BigBcd {
+        self.shl2(k)
+    }
+}
+
+impl Shr<usize> for &BigBcd {
+    type Output = BigBcd;
+
+    fn shr(self, k: usize) -> BigBcd {
+        self.shr2(k)
+    }
+}
+
+impl Shl<usize> for BigBcd {
+    type Output = BigBcd;
+
+    fn shl(self, k: usize) -> BigBcd {
+        self.shl2(k)
+    }
+}
+
+impl Shr<usize> for BigBcd {
+    type Output = BigBcd;
+
+    fn shr(self, k: usize) -> BigBcd {
+        self.shr2(k)
+    }
+}
+
+impl ShlAssign<usize> for BigBcd {
+    fn shl_assign(&mut self, k: usize) {
+        *self = self.shl2(k);
+    }
+}
+
+impl ShrAssign<usize> for BigBcd {
+    fn shr_assign(&mut self, k: usize) {
+        *self = self.shr2(k);
+    }
+}
+
+impl BigBcd {
+    fn mul_primitive_assign<T: Primitive>(&mut self, other: T) {
+        let (mag, neg) = other.split();
+        if mag <= u64::MAX as u128 {
+            self.mul_small_assign(mag as u64);
+            self.neg ^= neg;
+            self.fix_sign();
+        } else {
+            *self *= &BigBcd::from_mag(mag, neg);
+        }
+    }
+
+    // Truncated division by a primitive, leaving the quotient in self and
+    // returning the remainder.
+    fn div_rem_primitive_assign<T: Primitive>(&mut self, other: T) -> BigBcd {
+        let (mag, neg) = other.split();
+        assert!(mag != 0, "attempt to divide by zero");
+        if mag <= u64::MAX as u128 {
+            let rem_neg = self.neg;
+            let rem = self.div_rem_small_assign(mag as u64);
+            self.neg ^= neg;
+            self.fix_sign();
+            BigBcd::from_mag(rem as u128, rem_neg)
+        } else {
+            let (q, r) = self.div_rem(&BigBcd::from_mag(mag, neg)).unwrap();
+            *self = q;
+            r
+        }
+    }
+}
+
+macro_rules! primitive_ops {
+    ($($t:ty)*) => {$(
+        impl AddAssign<$t> for BigBcd {
+            fn add_assign(&mut self, other: $t) {
+                *self += &BigBcd::from(other);
+            }
+        }
+
+        impl SubAssign<$t> for BigBcd {
+            fn sub_assign(&mut self, other: $t) {
+                *self -= &BigBcd::from(other);
+            }
+        }
+
+        impl MulAssign<$t> for BigBcd {
+            fn mul_assign(&mut self, other: $t) {
+                self.mul_primitive_assign(other);
+            }
+        }
+
+        impl DivAssign<$t> for BigBcd {
+            fn div_assign(&mut self, other: $t) {
+                self.div_rem_primitive_assign(other);
+            }
+        }
+
+        impl RemAssign<$t> for BigBcd {
+            fn rem_assign(&mut self, other: $t) {
+                *self = self.div_rem_primitive_assign(other);
+            }
+        }
+
+        impl BitAndAssign<$t> for BigBcd {
+            fn bitand_assign(&mut self, other: $t) {
+                *self &= &BigBcd::from(other);
+            }
+        }
+
+        impl BitOrAssign<$t> for BigBcd {
+            fn bitor_assign(&mut self, other: $t) {
+                *self |= &BigBcd::from(other);
+            }
+        }
+
+        impl BitXorAssign<$t> for BigBcd {
+            fn bitxor_assign(&mut self, other: $t) {
+                *self ^= &BigBcd::from(other);
+            }
+        }
+
+        primitive_binop!($t, Add, add, add_assign);
+        primitive_binop!($t, Sub, sub, sub_assign);
+        primitive_binop!($t, Mul, mul, mul_assign);
+        primitive_binop!($t, Div, div, div_assign);
+        primitive_binop!($t, Rem, rem, rem_assign);
+        primitive_binop!($t, BitAnd, bitand, bitand_assign);
+        primitive_binop!($t, BitOr, bitor, bitor_assign);
+        primitive_binop!($t, BitXor, bitxor, bitxor_assign);
+    )*};
+}
+
+// `BigBcd op prim`, `&BigBcd op prim`, `prim op BigBcd` and `prim op &BigBcd`.
+macro_rules! primitive_binop {
+    ($t:ty, $imp:ident, $method:ident, $assign_method:ident) => {
+        impl $imp<$t> for BigBcd {
+            type Output = BigBcd;
+
+            fn $method(mut self, other: $t) -> BigBcd {
+                self.$assign_method(other);
+                self
+            }
+        }
+
+        impl $imp<$t> for &BigBcd {
+            type Output = BigBcd;
+
+            fn $method(self, other: $t) -> BigBcd {
+                let mut res = self.clone();
+                res.$assign_method(other);
+                res
+            }
+        }
+
+        impl $imp<BigBcd> for $t {
+            type Output = BigBcd;
+
+            fn $method(self, other: BigBcd) -> BigBcd {
+                let mut res = BigBcd::from(self);
+                res.$assign_method(&other);
+                res
+            }
+        }
+
+        impl $imp<&BigBcd> for $t {
+            type Output = BigBcd;
+
+            fn $method(self, other: &BigBcd) -> BigBcd {
+                let mut res = BigBcd::from(self);
+                res.$assign_method(other);
+                res
+            }
+        }
+    };
+}
+
+primitive_ops!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
@@ -0,0 +1,221 @@
+// Conversions to and from the binary-coded-decimal formats used by mainframe
+// and financial systems:
+//
+// - packed BCD: two digits per byte, high nibble first, unsigned;
+// - IBM packed decimal (COBOL COMP-3): packed BCD followed by a sign nibble;
+// - zoned decimal: one digit per byte in the low nibble, zone nibble 0xF,
+//   with the sign carried in the zone of the last byte.
+//
+// Sign nibbles follow IBM conventions: A, C, E and F are positive and B and D
+// negative. Encoding writes C for positive and D for negative values.
+
+use super::BigBcd;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    // Most significant byte first.
+    BigEndian,
+    // Least significant byte first.
+    LittleEndian,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BcdError {
+    Empty,
+    // A nibble that should hold a digit was above 9. `position` is the index
+    // of the byte in the input.
+    InvalidNibble { position: usize, nibble: u8 },
+    InvalidSign { nibble: u8 },
+    InvalidZone { position: usize, zone: u8 },
+    // Plain packed BCD has no sign.
+    Negative,
+    // The value has more digits than the requested width.
+    Overflow { digits: usize, width: usize },
+}
+
+impl fmt::Display for BcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BcdError::Empty => write!(f, "no BCD bytes to decode"),
+            BcdError::InvalidNibble { position, nibble } => {
+                write!(f, "invalid BCD digit {:#x} in byte {}", nibble, position)
+            }
+            BcdError::InvalidSign { nibble } => write!(f, "invalid sign nibble {:#x}", nibble),
+            BcdError::InvalidZone { position, zone } => {
+                write!(f, "invalid zone {:#x} in byte {}", zone, position)
+            }
+            BcdError::Negative => write!(f, "packed BCD cannot hold a negative value"),
+            BcdError::Overflow { digits, width } => {
+                write!(f, "{} digits do not fit in a width of {}", digits, width)
+            }
+        }
+    }
+}
+
+impl Error for BcdError {}
+
+const SIGN_POSITIVE: u8 = 0xC;
+const SIGN_NEGATIVE: u8 = 0xD;
+const ZONE: u8 = 0xF;
+
+fn sign_is_negative(nibble: u8) -> Result<bool, BcdError> {
+    match nibble {
+        0xA | 0xC | 0xE | 0xF => Ok(false),
+        0xB | 0xD => Ok(true),
+        _ => Err(BcdError::InvalidSign { nibble }),
+    }
+}
+
+fn check_digit(nibble: u8, position: usize) -> Result<u8, BcdError> {
+    if nibble > 9 {
+        Err(BcdError::InvalidNibble { position, nibble })
+    } else {
+        Ok(nibble)
+    }
+}
+
+// Packs nibbles two to a byte, high nibble first, padding with a leading 0
+// when there is an odd number of them.
+fn pack_nibbles(nibbles: &[u8]) -> Vec<u8> {
+    let pad = nibbles.len() % 2;
+    let mut padded = vec![0; pad];
+    padded.extend_from_slice(nibbles);
+    padded
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect()
+}
+
+// Left-pads digits with zeros to width, if given.
+fn widen(mut digits: Vec<u8>, width: Option<usize>) -> Result<Vec<u8>, BcdError> {
+    if let Some(width) = width {
+        if digits.len() > width {
+            return Err(BcdError::Overflow {
+                digits: digits.len(),
+                width,
+            });
+        }
+        let mut padded = vec![0; width - digits.len()];
+        padded.append(&mut digits);
+        digits = padded;
+    }
+    Ok(digits)
+}
+
+impl BigBcd {
+    // Encodes an unsigned value as packed BCD using as few bytes as possible.
+    pub fn to_packed_bcd(&self, order: ByteOrder) -> Result<Vec<u8>, BcdError> {
+        self.to_packed_bcd_bytes(order, None)
+    }
+
+    // Encodes an unsigned value as packed BCD in exactly `len` bytes.
+    pub fn to_packed_bcd_bytes(
+        &self,
+        order: ByteOrder,
+        len: Option<usize>,
+    ) -> Result<Vec<u8>, BcdError> {
+        if self.neg {
+            return Err(BcdError::Negative);
+        }
+        let mut bytes = pack_nibbles(&widen(self.to_digits_be(), len.map(|l| 2 * l))?);
+        if order == ByteOrder::LittleEndian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    pub fn from_packed_bcd(bytes: &[u8], order: ByteOrder) -> Result<Self, BcdError> {
+        if bytes.is_empty() {
+            return Err(BcdError::Empty);
+        }
+        let mut digits = Vec::with_capacity(bytes.len() * 2);
+        let mut push = |position: usize, byte: u8| -> Result<(), BcdError> {
+            digits.push(check_digit(byte >> 4, position)?);
+            digits.push(check_digit(byte & 0xF, position)?);
+            Ok(())
+        };
+        match order {
+            ByteOrder::BigEndian => {
+                for (i, &byte) in bytes.iter().enumerate() {
+                    push(i, byte)?;
+                }
+            }
+            ByteOrder::LittleEndian => {
+                for (i, &byte) in bytes.iter().enumerate().rev() {
+                    push(i, byte)?;
+                }
+            }
+        }
+        Ok(BigBcd::from_digits_be(&digits, false))
+    }
+
+    // Encodes as IBM packed decimal (COMP-3) using as few bytes as possible.
+    pub fn to_comp3(&self) -> Vec<u8> {
+        self.to_comp3_digits(None).unwrap()
+    }
+
+    // Encodes as a `PIC S9(digits) COMP-3` field, which is
+    // digits / 2 + 1 bytes long.
+    pub fn to_comp3_digits(&self, digits: Option<usize>) -> Result<Vec<u8>, BcdError> {
+        let mut nibbles = widen(self.to_digits_be(), digits)?;
+        nibbles.push(if self.neg {
+            SIGN_NEGATIVE
+        } else {
+            SIGN_POSITIVE
+        });
+        Ok(pack_nibbles(&nibbles))
+    }
+
+    pub fn from_comp3(bytes: &[u8]) -> Result<Self, BcdError> {
+        let (&last, rest) = bytes.split_last().ok_or(BcdError::Empty)?;
+        let mut digits = Vec::with_capacity(bytes.len() * 2);
+        for (i, &byte) in rest.iter().enumerate() {
+            digits.push(check_digit(byte >> 4, i)?);
+            digits.push(check_digit(byte & 0xF, i)?);
+        }
+        digits.push(check_digit(last >> 4, rest.len())?);
+        let neg = sign_is_negative(last & 0xF)?;
+        Ok(BigBcd::from_digits_be(&digits, neg))
+    }
+
+    // Encodes as EBCDIC zoned decimal using one byte per digit.
+    pub fn to_zoned(&self) -> Vec<u8> {
+        self.to_zoned_digits(None).unwrap()
+    }
+
+    // Encodes as a zoned decimal field of exactly `digits` bytes.
+    pub fn to_zoned_digits(&self, digits: Option<usize>) -> Result<Vec<u8>, BcdError> {
+        let mut bytes: Vec<u8> = widen(self.to_digits_be(), digits)?
+            .into_iter()
+            .map(|d| (ZONE << 4) | d)
+            .collect();
+        let sign = if self.neg {
+            SIGN_NEGATIVE
+        } else {
+            SIGN_POSITIVE
+        };
+        let last = bytes.last_mut().unwrap();
+        *last = (sign << 4) | (*last & 0xF);
+        Ok(bytes)
+    }
+
+    pub fn from_zoned(bytes: &[u8]) -> Result<Self, BcdError> {
+        if bytes.is_empty() {
+            return Err(BcdError::Empty);
+        }
+        let mut digits = Vec::with_capacity(bytes.len());
+        for (i, &byte) in bytes.iter().enumerate() {
+            if i + 1 < bytes.len() && byte >> 4 != ZONE {
+                return Err(BcdError::InvalidZone {
+                    position: i,
+                    zone: byte >> 4,
+                });
+            }
+            digits.push(check_digit(byte & 0xF, i)?);
+        }
+        let neg = sign_is_negative(bytes[bytes.len() - 1] >> 4)?;
+        Ok(BigBcd::from_digits_be(&digits, neg))
+    }
+}
@@ -0,0 +1,116 @@
+// Splitting independent halves of the big recursive algorithms across
+// threads: the products in Toom-3 and in unbalanced multiplication, the three
+// transforms in `ntt`, product trees and binary splitting, and the
+// divide-and-conquer radix conversions. Karatsuba's operands are always
+// below MIN_LIMBS, so it stays serial.
+//
+// Only with the `parallel` feature; otherwise `join` runs both halves in
+// order on the calling thread. Either way each half computes exactly what it
+// would serially, so the results never depend on the thread count.
+
+// Work below this many limbs is not worth a thread.
+const MIN_LIMBS: usize = 1000;
+
+#[cfg(feature = "parallel")]
+mod pool {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    // The configured thread count, or 0 for the available parallelism.
+    static THREADS: AtomicUsize = AtomicUsize::new(0);
+    // Threads spawned by `join` and still running.
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    // Sets how many threads BigBcd arithmetic may use in total, counting the
+    // calling thread. 1 makes everything serial, 0 restores the default of
+    // one per available core.
+    pub fn set_threads(n: usize) {
+        THREADS.store(n, Ordering::Relaxed);
+    }
+
+    pub fn threads() -> usize {
+        match THREADS.load(Ordering::Relaxed) {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+
+    // Gives the slot taken in `try_spawn` back, even if the thread panics.
+    struct Slot;
+
+    impl Drop for Slot {
+        fn drop(&mut self) {
+            SPAWNED.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    fn take_slot() -> Option<Slot> {
+        let limit = threads();
+        SPAWNED
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                (n + 1 < limit).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Slot)
+    }
+
+    pub(super) fn join<RA: Send, RB: Send>(
+        a: impl FnOnce() -> RA + Send,
+        b: impl FnOnce() -> RB + Send,
+    ) -> (RA, RB) {
+        let slot = match take_slot() {
+            Some(slot) => slot,
+            None => return (a(), b()),
+        };
+        thread::scope(|s| {
+            let handle = s.spawn(move || {
+                let _slot = slot;
+                b()
+            });
+            let ra = a();
+            match handle.join() {
+                Ok(rb) => (ra, rb),
+                Err(e) => std::panic::resume_unwind(e),
+            }
+        })
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+mod pool {
+    pub(super) fn join<RA, RB>(a: impl FnOnce() -> RA, b: impl FnOnce() -> RB) -> (RA, RB) {
+        (a(), b())
+    }
+}
+
+#[cfg(feature = "parallel")]
+pub use pool::{set_threads, threads};
+
+// (a(), b()), on two threads if there is one free and `limbs`, the size of
+// the work, makes it worthwhile.
+pub(super) fn join<RA: Send, RB: Send>(
+    limbs: usize,
+    a: impl FnOnce() -> RA + Send,
+    b: impl FnOnce() -> RB + Send,
+) -> (RA, RB) {
+    if limbs >= MIN_LIMBS {
+        pool::join(a, b)
+    } else {
+        (a(), b())
+    }
+}
+
+// items.iter().map(f).collect(), splitting the items between threads.
+pub(super) fn map<T: Sync, R: Send>(
+    limbs: usize,
+    items: &[T],
+    f: &(impl Fn(&T) -> R + Sync),
+) -> Vec<R> {
+    if items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let (lo, hi) = items.split_at(items.len() / 2);
+    let (mut lo, hi) = join(limbs, || map(limbs, lo, f), || map(limbs, hi, f));
+    lo.extend(hi);
+    lo
+}
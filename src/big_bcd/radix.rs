@@ -0,0 +1,255 @@
+// Conversion to and from other bases, as strings in radix 2..=36 and as raw
+// bytes.
+//
+// Both directions work on "chunks": digits in a base C that is a power of
+// the target radix (or 2^32 for bytes) and small enough for the one-limb
+// fast paths. Small values are converted a chunk at a time. Large ones are
+// split by divide and conquer around the powers C^(2^j), so the conversion
+// costs O(M(n) log n) on top of the fast multiplication and division.
+
+use super::{parallel, parse_digits, BigBcd, ParseError};
+use std::fmt;
+
+// Below this many limbs or chunks, convert one chunk at a time.
+const SMALL: usize = 30;
+
+// The digits of |x| in base `chunk`, least significant first. Empty for zero.
+pub(super) fn to_chunks(x: &BigBcd, chunk: u64) -> Vec<u64> {
+    let x = x.abs();
+    let mut powers = vec![BigBcd::from_mag(chunk as u128, false)];
+    loop {
+        let last = &powers[powers.len() - 1];
+        if last.limbs.len() * 2 > x.limbs.len() + 1 {
+            break;
+        }
+        let next = last.multiply(last);
+        powers.push(next);
+    }
+
+    let mut res = vec![];
+    chunks_into(x, powers.len() - 1, &powers, chunk, false, &mut res);
+    while res.last() == Some(&0) {
+        res.pop();
+    }
+    res
+}
+
+// Appends the chunks of x < powers[j]^2 to out, padded to 2^(j + 1) chunks
+// if `pad` is set.
+fn chunks_into(
+    mut x: BigBcd,
+    j: usize,
+    powers: &[BigBcd],
+    chunk: u64,
+    pad: bool,
+    out: &mut Vec<u64>,
+) {
+    if j == 0 || x.limbs.len() <= SMALL {
+        let start = out.len();
+        while !x.is_zero() {
+            out.push(x.div_rem_small_assign(chunk));
+        }
+        if pad {
+            out.resize(start + (1 << (j + 1)), 0);
+        }
+        return;
+    }
+    let (q, r) = x.div_rem(&powers[j]).unwrap();
+    let (lo, hi) = parallel::join(
+        x.limbs.len(),
+        || {
+            let mut lo = vec![];
+            chunks_into(r, j - 1, powers, chunk, true, &mut lo);
+            lo
+        },
+        || {
+            let mut hi = vec![];
+            chunks_into(q, j - 1, powers, chunk, pad, &mut hi);
+            hi
+        },
+    );
+    out.extend(lo);
+    out.extend(hi);
+}
+
+// The value of the base `chunk` digits, least significant first.
+pub(super) fn from_chunks(chunks: &[u64], chunk: u64) -> BigBcd {
+    let mut powers = vec![BigBcd::from_mag(chunk as u128, false)];
+    while 1 << powers.len() < chunks.len() {
+        let last = &powers[powers.len() - 1];
+        let next = last.multiply(last);
+        powers.push(next);
+    }
+    from_chunks_rec(chunks, &powers, chunk)
+}
+
+fn from_chunks_rec(chunks: &[u64], powers: &[BigBcd], chunk: u64) -> BigBcd {
+    if chunks.len() <= SMALL {
+        let mut res = BigBcd::from(0);
+        for &c in chunks.iter().rev() {
+            res.mul_small_assign(chunk);
+            res.add_mag_assign(&BigBcd::from_mag(c as u128, false));
+        }
+        return res;
+    }
+    // Split off the largest power-of-two number of low chunks.
+    let mut j = 0;
+    while 2 << j < chunks.len() {
+        j += 1;
+    }
+    let (lo, hi) = chunks.split_at(1 << j);
+    let (lo, hi) = parallel::join(
+        chunks.len(),
+        || from_chunks_rec(lo, powers, chunk),
+        || from_chunks_rec(hi, powers, chunk),
+    );
+    let mut res = hi.multiply(&powers[j]);
+    res.add_mag_assign(&lo);
+    res
+}
+
+// The largest k with radix^k <= 10^9, and radix^k.
+fn chunk_for(radix: u32) -> (usize, u64) {
+    let (mut k, mut chunk) = (1, radix as u64);
+    while chunk * radix as u64 <= 1_000_000_000 {
+        chunk *= radix as u64;
+        k += 1;
+    }
+    (k, chunk)
+}
+
+impl BigBcd {
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        if !(2..=36).contains(&radix) {
+            return Err(ParseError::InvalidRadix(radix));
+        }
+        let (neg, digits) = parse_digits(s, radix)?;
+        if radix == 10 {
+            return Ok(BigBcd::from_digits_be(&digits, neg));
+        }
+
+        let (k, chunk) = chunk_for(radix);
+        let chunks: Vec<u64> = digits
+            .rchunks(k)
+            .map(|c| c.iter().fold(0, |acc, &d| acc * radix as u64 + d as u64))
+            .collect();
+        let mut res = from_chunks(&chunks, chunk);
+        res.neg = neg;
+        res.fix_sign();
+        Ok(res)
+    }
+
+    // Lowercase digits in radix 2..=36, with a leading '-' if negative.
+    // Panics on other radixes.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let mag = self.to_str_radix_mag(radix, false);
+        if self.neg {
+            format!("-{}", mag)
+        } else {
+            mag
+        }
+    }
+
+    fn to_str_radix_mag(&self, radix: u32, upper: bool) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix {} is not in 2..=36",
+            radix
+        );
+        if radix == 10 {
+            return self.abs().to_string();
+        }
+
+        let (k, chunk) = chunk_for(radix);
+        let chunks = to_chunks(self, chunk);
+        let mut digits = Vec::with_capacity(chunks.len() * k);
+        for &c in chunks.iter() {
+            let mut c = c;
+            for _ in 0..k {
+                digits.push((c % radix as u64) as u32);
+                c /= radix as u64;
+            }
+        }
+        while digits.len() > 1 && digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            digits.push(0);
+        }
+
+        digits
+            .iter()
+            .rev()
+            .map(|&d| {
+                let c = std::char::from_digit(d, radix).unwrap();
+                if upper {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    // The bytes of |self|, most significant first. Zero is [0].
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    // The bytes of |self|, least significant first. Zero is [0].
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = to_chunks(self, 1 << 32)
+            .iter()
+            .flat_map(|&word| (word as u32).to_le_bytes().to_vec())
+            .collect();
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    // The non-negative value of big-endian bytes.
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let mut le = bytes.to_vec();
+        le.reverse();
+        BigBcd::from_bytes_le(&le)
+    }
+
+    // The non-negative value of little-endian bytes.
+    pub fn from_bytes_le(bytes: &[u8]) -> Self {
+        let words: Vec<u64> = bytes
+            .chunks(4)
+            .map(|c| c.iter().rev().fold(0, |acc, &b| (acc << 8) | b as u64))
+            .collect();
+        from_chunks(&words, 1 << 32)
+    }
+}
+
+impl fmt::LowerHex for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "0x", &self.to_str_radix_mag(16, false))
+    }
+}
+
+impl fmt::UpperHex for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "0x", &self.to_str_radix_mag(16, true))
+    }
+}
+
+impl fmt::Binary for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "0b", &self.to_str_radix_mag(2, false))
+    }
+}
+
+impl fmt::Octal for BigBcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "0o", &self.to_str_radix_mag(8, false))
+    }
+}
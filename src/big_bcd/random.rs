@@ -0,0 +1,178 @@
+// Random BigBcds: uniform below a bound, with a given number of digits, and
+// random probable primes, plus the Miller-Rabin test they rely on.
+//
+// Everything draws from a `RandomSource`. `XorShiftRng` is a small built-in
+// one whose whole output follows from its seed, so a failing case can be
+// replayed by logging `seed()`. With the `rand` feature any `rand::RngCore`
+// is a RandomSource too.
+
+use super::{BigBcd, ModContext, BASE};
+use crate::primes::sieve;
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub trait RandomSource {
+    // 64 uniformly random bits.
+    fn next_u64(&mut self) -> u64;
+}
+
+// xorshift64*, from Vigna's "An experimental exploration of Marsaglia's
+// xorshift generators, scrambled". Fast and statistically fine for tests and
+// for picking Miller-Rabin bases, but not cryptographically secure.
+#[derive(Clone, Debug)]
+pub struct XorShiftRng {
+    seed: u64,
+    state: u64,
+}
+
+impl XorShiftRng {
+    pub fn new(seed: u64) -> Self {
+        // The state must not be zero, and nearby seeds should not give
+        // similar streams, so the seed goes through splitmix64 first.
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        XorShiftRng {
+            seed,
+            state: if z == 0 { 0x9e3779b97f4a7c15 } else { z },
+        }
+    }
+
+    // A generator with a seed that differs between runs, from the time and
+    // the per-process hashing keys.
+    pub fn from_entropy() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        if let Ok(t) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            hasher.write_u128(t.as_nanos());
+        }
+        XorShiftRng::new(hasher.finish())
+    }
+
+    // The seed this generator was created from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RandomSource for XorShiftRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<R: rand::RngCore + ?Sized> RandomSource for R {
+    fn next_u64(&mut self) -> u64 {
+        rand::RngCore::next_u64(self)
+    }
+}
+
+// Uniform in [0, m), for m > 0. Draws in [0, 2^64 mod m) are rejected, which
+// leaves a multiple of m values to reduce.
+fn below<R: RandomSource + ?Sized>(rng: &mut R, m: u64) -> u64 {
+    let reject = (u64::MAX % m + 1) % m;
+    loop {
+        let x = rng.next_u64();
+        if x >= reject {
+            return x % m;
+        }
+    }
+}
+
+// |x| mod m for a machine-word m.
+fn rem_small(x: &BigBcd, m: u64) -> u64 {
+    let rem = x.limbs.iter().rev().fold(0, |rem: u128, &limb| {
+        (rem * BASE as u128 + limb as u128) % m as u128
+    });
+    rem as u64
+}
+
+// Miller-Rabin rounds in `random_prime`. A composite passes one round with
+// probability at most 1/4, and far less for random candidates.
+const PRIME_ROUNDS: usize = 32;
+
+impl BigBcd {
+    // Uniform in [0, bound). Panics unless bound > 0.
+    pub fn random_below<R: RandomSource + ?Sized>(bound: &BigBcd, rng: &mut R) -> Self {
+        assert!(bound.is_positive(), "random_below needs a positive bound");
+        // Uniform over [0, (top + 1) * BASE^(n - 1)) until below the bound,
+        // which happens at least half the time.
+        let n = bound.limbs.len();
+        let top = bound.limbs[n - 1] as u64;
+        loop {
+            let mut limbs: Vec<u32> = (1..n).map(|_| below(rng, BASE as u64) as u32).collect();
+            limbs.push(below(rng, top + 1) as u32);
+            let mut x = BigBcd { limbs, neg: false };
+            x.trim();
+            if x.cmp_mag(bound) == Ordering::Less {
+                return x;
+            }
+        }
+    }
+
+    // Uniform among the numbers with exactly n digits. Panics if n is 0.
+    pub fn random_with_digits<R: RandomSource + ?Sized>(n: usize, rng: &mut R) -> Self {
+        assert!(n > 0, "a number has at least one digit");
+        let lo = BigBcd::pow10(n - 1);
+        let span = BigBcd::pow10(n).subtract(&lo);
+        BigBcd::random_below(&span, rng).add(&lo)
+    }
+
+    // A uniformly random probable prime with exactly `digits` digits. Panics
+    // if digits is 0.
+    pub fn random_prime<R: RandomSource + ?Sized>(digits: usize, rng: &mut R) -> Self {
+        loop {
+            let x = BigBcd::random_with_digits(digits, rng);
+            if x.is_probable_prime(PRIME_ROUNDS, rng) {
+                return x;
+            }
+        }
+    }
+
+    // Trial division by the primes below 1000, then `rounds` rounds of
+    // Miller-Rabin with random bases. Never wrong for primes; a composite
+    // passes with probability at most 4^-rounds.
+    pub fn is_probable_prime<R: RandomSource + ?Sized>(&self, rounds: usize, rng: &mut R) -> bool {
+        if self.neg || *self < BigBcd::from(2) {
+            return false;
+        }
+        for p in sieve(1000) {
+            let p = p as u64;
+            if rem_small(self, p) == 0 {
+                return *self == BigBcd::from(p);
+            }
+        }
+        if *self < BigBcd::from(1_000_000) {
+            return true;
+        }
+
+        // self - 1 = d 2^s with d odd.
+        let minus_one = self.subtract(&BigBcd::from(1));
+        let s = minus_one.trailing_zeros().unwrap() as usize;
+        let d = minus_one.shr2(s);
+        let ctx = ModContext::new(self).unwrap();
+        let (one, minus_one) = (ctx.to_residue(&BigBcd::from(1)), ctx.to_residue(&minus_one));
+        let base_span = self.subtract(&BigBcd::from(3));
+        'rounds: for _ in 0..rounds {
+            let a = BigBcd::random_below(&base_span, rng).add(&BigBcd::from(2));
+            let mut x = ctx.pow_mod(&ctx.to_residue(&a), &d);
+            if x == one || x == minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = ctx.sqr_mod(&x);
+                if x == minus_one {
+                    continue 'rounds;
+                }
+            }
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,151 @@
+// Integer roots and perfect-power detection.
+//
+// Roots are found with Newton's iteration x' = ((k - 1) x + n / x^(k - 1)) / k
+// on integers. From any start above the floor root the iterates decrease
+// until they reach it. The start comes from a floating-point estimate of the
+// leading digits, so only a few full-precision steps are needed.
+
+use super::BigBcd;
+use crate::primes::sieve;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootError {
+    ZeroDegree,
+    EvenRootOfNegative,
+}
+
+impl fmt::Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootError::ZeroDegree => write!(f, "cannot take the 0th root"),
+            RootError::EvenRootOfNegative => write!(f, "even root of a negative BigBcd"),
+        }
+    }
+}
+
+impl Error for RootError {}
+
+// An upper bound on |n|^(1/k) from its leading digits, within a relative
+// 10^-4 or so.
+fn estimate(n: &BigBcd, k: u32) -> BigBcd {
+    const MARGIN: f64 = 1.0 + 1e-4;
+    let digits = n.to_digits_be();
+    let lead = digits.len().min(15);
+    let m = digits[..lead]
+        .iter()
+        .fold(0.0, |acc, &d| acc * 10.0 + d as f64);
+    let exp = (digits.len() - lead) as f64;
+
+    let t = (m.log10() + exp) / k as f64;
+    if t < 14.0 {
+        return BigBcd::from((10f64.powf(t) * MARGIN).ceil() as usize + 1);
+    }
+    let shift = t.floor() as usize - 13;
+    let lead = (10f64.powf(t - shift as f64) * MARGIN).ceil() as usize + 1;
+    BigBcd::from(lead).shl10(shift)
+}
+
+// floor(|n|^(1/k)) for n != 0 and k >= 2. Starting above the root, each
+// step decreases x until it reaches the root.
+fn root_mag(n: &BigBcd, k: u32) -> BigBcd {
+    let n = n.abs();
+    // n < 10^d <= 2^(4d), so the root is 1 when k >= 4d.
+    if k as usize >= 4 * n.num_digits() {
+        return BigBcd::from(1);
+    }
+
+    let mut x = estimate(&n, k);
+    loop {
+        let mut y = x.clone();
+        y.mul_small_assign(k as u64 - 1);
+        y += &n.divide(&x.pow(k - 1)).unwrap();
+        y.div_rem_small_assign(k as u64);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+impl BigBcd {
+    // (s, r) with s = floor(sqrt(self)) and self == s^2 + r.
+    pub fn isqrt(&self) -> Result<(Self, Self), RootError> {
+        self.iroot(2)
+    }
+
+    // (s, r) with s^k + r == self, where s is the k-th root rounded towards
+    // zero. r has the sign of self.
+    pub fn iroot(&self, k: u32) -> Result<(Self, Self), RootError> {
+        if k == 0 {
+            return Err(RootError::ZeroDegree);
+        }
+        if self.neg && k.is_multiple_of(2) {
+            return Err(RootError::EvenRootOfNegative);
+        }
+        if k == 1 || self.is_zero() {
+            return Ok((self.clone(), BigBcd::from(0)));
+        }
+
+        let mut root = root_mag(self, k);
+        root.neg = self.neg;
+        let rem = self.subtract(&root.pow(k));
+        Ok((root, rem))
+    }
+
+    pub fn is_perfect_square(&self) -> bool {
+        // Squares end in 0, 1, 4, 5, 6 or 9.
+        if self.neg || [2, 3, 7, 8].contains(&(self.limbs[0] % 10)) {
+            return false;
+        }
+        self.isqrt().unwrap().1.is_zero()
+    }
+
+    // The base and the largest exponent e >= 2 with base^e == self, if there
+    // is one. 0, 1 and -1 are powers with any exponent, so give None.
+    pub fn is_perfect_power(&self) -> Option<(Self, u32)> {
+        if self.abs() <= BigBcd::from(1) {
+            return None;
+        }
+
+        // Take prime roots while they are exact; the exponent is the product
+        // of the primes taken. |base| >= 2, so only primes up to log2 |self|
+        // can divide it.
+        let mut base = self.clone();
+        let mut exp = 1;
+        for p in sieve(4 * self.num_digits()) {
+            let p = p as u32;
+            if self.neg && p == 2 {
+                continue;
+            }
+            while (p as usize) < 4 * base.num_digits() {
+                let (root, rem) = base.iroot(p).unwrap();
+                if !rem.is_zero() || root.abs() <= BigBcd::from(1) {
+                    break;
+                }
+                base = root;
+                exp *= p;
+            }
+        }
+        if exp > 1 {
+            Some((base, exp))
+        } else {
+            None
+        }
+    }
+
+    // The first n significant digits of sqrt(self), truncated.
+    pub fn sqrt_digits(&self, n: usize) -> Result<Self, RootError> {
+        if self.neg {
+            return Err(RootError::EvenRootOfNegative);
+        }
+        let int_digits = self.num_digits().div_ceil(2);
+        if n >= int_digits {
+            let scaled = self.shl10(2 * (n - int_digits));
+            Ok(scaled.isqrt()?.0)
+        } else {
+            Ok(self.isqrt()?.0.shr10(int_digits - n))
+        }
+    }
+}
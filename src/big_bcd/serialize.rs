@@ -0,0 +1,55 @@
+// serde support, behind the `serde` feature. Human-readable formats get the
+// decimal string, as in "-12345". Binary formats get a sign byte, 0 or 1,
+// followed by the bytes of the magnitude least significant first, which is
+// about 17% smaller than the digits packed two to a byte.
+
+use super::BigBcd;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+impl Serialize for BigBcd {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.collect_str(self);
+        }
+        let mut bytes = vec![self.neg as u8];
+        bytes.extend(self.to_bytes_le());
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+struct BigBcdVisitor;
+
+impl<'de> Visitor<'de> for BigBcdVisitor {
+    type Value = BigBcd;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal integer string or a sign byte and magnitude bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<BigBcd, E> {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<BigBcd, E> {
+        let (&sign, mag) = match bytes.split_first() {
+            Some(split) if split.0 <= &1 => split,
+            _ => return Err(E::invalid_value(de::Unexpected::Bytes(bytes), &self)),
+        };
+        let mut res = BigBcd::from_bytes_le(mag);
+        res.neg = sign == 1;
+        res.fix_sign();
+        Ok(res)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigBcd {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BigBcd, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BigBcdVisitor)
+        } else {
+            deserializer.deserialize_bytes(BigBcdVisitor)
+        }
+    }
+}
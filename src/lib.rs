@@ -15,6 +15,10 @@ mod tests {
     use crate::ratio::Ratio;
     use crate::rope::Rope;
 
+    fn big(s: &str) -> BigBcd {
+        s.parse().unwrap()
+    }
+
     #[test]
     fn bool_arr_test() {
         let mut br = BoolArr::new(10, false);
@@ -148,9 +152,9 @@ mod tests {
         let a = BigBcd::from(1000);
         let b = BigBcd::from(1234);
         assert!(a.add(&b) == BigBcd::from(1000+1234));
-        let a = BigBcd::from("10124302141");
-        let b = BigBcd::from("21041232112521123124215151512512");
-        assert!(a.add(&b) == BigBcd::from("21041232112521123124225275814653"));
+        let a = big("10124302141");
+        let b = big("21041232112521123124215151512512");
+        assert!(a.add(&b) == big("21041232112521123124225275814653"));
     }
 
     #[test]
@@ -160,11 +164,11 @@ mod tests {
         let c = b.subtract(&a);
         println!("{}", c);
         assert!(c == BigBcd::from(234));
-        let a = BigBcd::from("10124302141");
-        let b = BigBcd::from("21041232112521123124215151512512");
+        let a = big("10124302141");
+        let b = big("21041232112521123124215151512512");
         let c = b.subtract(&a);
         println!("{}", c);
-        assert!(c == BigBcd::from("21041232112521123124205027210371"));
+        assert!(c == big("21041232112521123124205027210371"));
     }
 
     #[test]
@@ -174,32 +178,1199 @@ mod tests {
         let c = b.multiply(&a);
         println!("{}", c);
         assert!(c == BigBcd::from(1234000));
-        let a = BigBcd::from("10124302141");
-        let b = BigBcd::from("21041232112521123124215151512512");
+        let a = big("10124302141");
+        let b = big("21041232112521123124215151512512");
         let c = b.multiply(&a);
         println!("{}", c);
-        assert!(c == BigBcd::from("213027791326075559754216067402764629888192"));
+        assert!(c == big("213027791326075559754216067402764629888192"));
     }
 
     #[test]
     fn div_rem_test() {
         let a = BigBcd::from(1000);
         let b = BigBcd::from(1234);
-        let (c, d) = b.div_rem(&a);
+        let (c, d) = b.div_rem(&a).unwrap();
         println!("{}, {}", c, d);
         assert!(c == BigBcd::from(1));
         assert!(d == BigBcd::from(234));
-        let a = BigBcd::from("10124302141");
-        let b = BigBcd::from("21041232112521123124215151512512");
-        let (c, d) = b.div_rem(&a);
+        let a = big("10124302141");
+        let b = big("21041232112521123124215151512512");
+        let (c, d) = b.div_rem(&a).unwrap();
         println!("{}, {}", c, d);
-        assert!(c == BigBcd::from("2078289626236187524326"));
-        assert!(d == BigBcd::from("3940130546"));
+        assert!(c == big("2078289626236187524326"));
+        assert!(d == big("3940130546"));
         assert!(c > a);
         assert!(BigBcd::from(9) > BigBcd::from(8));
         assert!(BigBcd::from(80) > BigBcd::from(9));
     }
 
+    #[test]
+    fn signed_add_sub_test() {
+        let a = BigBcd::from(1000);
+        let b = BigBcd::from(1234);
+        let c = a.subtract(&b);
+        println!("{}", c);
+        assert!(c == big("-234"));
+        assert!(c.add(&b) == a);
+        assert!(a.subtract(&a) == BigBcd::from(0));
+        assert!(!a.subtract(&a).is_negative());
+        let a = big("-10124302141");
+        let b = big("21041232112521123124215151512512");
+        assert!(a.add(&b) == big("21041232112521123124205027210371"));
+        assert!(a.subtract(&b) == big("-21041232112521123124225275814653"));
+        assert!(b.negated().subtract(&a) == big("-21041232112521123124205027210371"));
+        assert!(format!("{}", big("-0")) == "0");
+        assert!(format!("{}", big("-120")) == "-120");
+        assert!(big("-5") < big("-4"));
+        assert!(big("-50") < BigBcd::from(4));
+    }
+
+    #[test]
+    fn signed_mul_div_test() {
+        let a = big("-1234");
+        let b = BigBcd::from(1000);
+        assert!(a.multiply(&b) == big("-1234000"));
+        assert!(a.multiply(&a) == BigBcd::from(1522756));
+        assert!(a.multiply(&BigBcd::from(0)) == BigBcd::from(0));
+        assert!(BigBcd::from(1000).divide(&BigBcd::from(1)).unwrap() == BigBcd::from(1000));
+        for x in -25isize..=25 {
+            for y in [-7isize, -3, -1, 1, 2, 5, 9].iter() {
+                let (bx, by) = (big(&x.to_string()), big(&y.to_string()));
+                let (q, r) = bx.div_rem(&by).unwrap();
+                assert!(q == big(&(x / y).to_string()));
+                assert!(r == big(&(x % y).to_string()));
+                let (q, r) = bx.div_rem_euclid(&by).unwrap();
+                assert!(q == big(&x.div_euclid(*y).to_string()));
+                assert!(r == big(&x.rem_euclid(*y).to_string()));
+            }
+        }
+    }
+
+    #[test]
+    fn ops_test() {
+        let a = big("-10124302141");
+        let b = big("21041232112521123124215151512512");
+        assert!(&a + &b == a.add(&b));
+        assert!(a.clone() - b.clone() == a.subtract(&b));
+        assert!(&a * b.clone() == a.multiply(&b));
+        assert!(b.clone() / &a == b.divide(&a).unwrap());
+        assert!(&b % &a == b.rem(&a).unwrap());
+        assert!(-&a == big("10124302141"));
+        assert!(-(-a.clone()) == a);
+        let mut c = b.clone();
+        c += &a;
+        c -= a.clone();
+        assert!(c == b);
+        c *= &a;
+        c /= &a;
+        assert!(c == b);
+        c %= a.clone();
+        assert!(c == b.rem(&a).unwrap());
+        let mut d = BigBcd::from(999);
+        d += &BigBcd::from(1);
+        assert!(d == BigBcd::from(1000));
+        d -= &BigBcd::from(1001);
+        assert!(d == big("-1"));
+    }
+
+    #[test]
+    fn primitive_ops_test() {
+        let a = big("-10124302141");
+        assert!(&a + 5u64 == big("-10124302136"));
+        assert!(5u64 - &a == big("10124302146"));
+        assert!(a.clone() * 1000usize == big("-10124302141000"));
+        assert!(&a * -2i32 == big("20248604282"));
+        assert!(&a * u128::MAX == a.multiply(&big("340282366920938463463374607431768211455")));
+        assert!(&a / 7u8 == big("-1446328877"));
+        assert!(&a % 7u8 == big("-2"));
+        assert!(&a / -7i64 == big("1446328877"));
+        assert!(100i32 / BigBcd::from(7) == BigBcd::from(14));
+        let mut b = BigBcd::from(1);
+        for i in 1..=25u32 {
+            b *= i;
+        }
+        assert!(b == big("15511210043330985984000000"));
+        for i in 1..=25u32 {
+            b /= i;
+        }
+        assert!(b == BigBcd::from(1));
+        b *= 0u8;
+        assert!(b == BigBcd::from(0) && !b.is_negative());
+    }
+
+    #[test]
+    fn num_traits_test() {
+        use num_traits::{CheckedDiv, FromPrimitive, Num, One, Pow, Signed, ToPrimitive, Zero};
+        assert!(BigBcd::zero() == BigBcd::from(0) && BigBcd::one() == BigBcd::from(1));
+        assert!(<BigBcd as Num>::from_str_radix("-ff", 16) == Ok(big("-255")));
+        assert!(<BigBcd as Num>::from_str_radix("12a", 10).is_err());
+        assert!(big("-7").signum() == big("-1"));
+        assert!(BigBcd::from(2).pow(100u32) == big("1267650600228229401496703205376"));
+        assert!(BigBcd::from(5).checked_div(&BigBcd::zero()).is_none());
+        let x = BigBcd::from_i128(i128::MIN).unwrap();
+        assert!(x.to_i128() == Some(i128::MIN));
+        assert!(x.to_i64().is_none() && x.to_u128().is_none());
+        assert!(BigBcd::from_u64(u64::MAX).unwrap().to_u64() == Some(u64::MAX));
+        assert!(BigBcd::from_f64(-1e30).unwrap() == big("-1000000000000000019884624838656"));
+        assert!(BigBcd::from_f64(2.75).unwrap() == BigBcd::from(2));
+        assert!(BigBcd::from_f64(f64::NAN).is_none());
+        assert!(ToPrimitive::to_f64(&big("-1000000000000000019884624838656")) == Some(-1e30));
+    }
+
+    #[test]
+    fn convert_test() {
+        use std::convert::TryFrom;
+        assert!(u8::try_from(&BigBcd::from(u8::MAX)) == Ok(u8::MAX));
+        assert!(i8::try_from(&BigBcd::from(i8::MIN)) == Ok(i8::MIN));
+        assert!(u64::try_from(&BigBcd::from(u64::MAX)) == Ok(u64::MAX));
+        assert!(i128::try_from(&BigBcd::from(i128::MIN)) == Ok(i128::MIN));
+        assert!(u128::try_from(&BigBcd::from(u128::MAX)) == Ok(u128::MAX));
+        assert!(isize::try_from(&BigBcd::from(-5isize)) == Ok(-5));
+        assert!(u8::try_from(&BigBcd::from(256)).is_err());
+        assert!(i8::try_from(&BigBcd::from(-129)).is_err());
+        assert!(u32::try_from(&BigBcd::from(-1)).is_err());
+        assert!(u128::try_from(&(BigBcd::from(u128::MAX) + 1)).is_err());
+
+        assert!(BigBcd::from(0).to_f64() == 0.0);
+        assert!(BigBcd::from(-12345).to_f64() == -12345.0);
+        // 2^53 + 1 ties to the even 2^53, 2^53 + 3 to 2^53 + 4.
+        assert!(BigBcd::from(9007199254740993u64).to_f64() == 9007199254740992.0);
+        assert!(BigBcd::from(9007199254740995u64).to_f64() == 9007199254740996.0);
+        // Just above the tie at 2^100 + 2^47, which must round up.
+        let x: BigBcd = BigBcd::from(2).pow(100) + BigBcd::from(2).pow(47) + 1;
+        assert!(x.to_f64() == 2f64.powi(100) + 2f64.powi(48));
+        assert!(BigBcd::from(2).pow(1024).to_f64() == f64::INFINITY);
+        assert!((-BigBcd::from(10).pow(400)).to_f64() == f64::NEG_INFINITY);
+        let max = BigBcd::from_f64(f64::MAX).unwrap();
+        assert!(max.to_f64() == f64::MAX);
+
+        assert!(BigBcd::from_f64(f64::INFINITY).is_none());
+        assert!(BigBcd::from_f64(1e30).unwrap() == big("1000000000000000019884624838656"));
+        assert!(BigBcd::from_f64(-1.5).unwrap() == BigBcd::from(-1));
+        assert!(BigBcd::from_f64(-0.5).unwrap() == BigBcd::from(0));
+        assert!(BigBcd::from_f64(2f64.powi(1000)).unwrap() == BigBcd::from(2).pow(1000));
+        for x in [0.1, 3.0, 123456789.75, 1e15, 7e22, 1.5e300] {
+            assert!(BigBcd::from_f64(x).unwrap().to_f64() == x.trunc());
+        }
+    }
+
+    #[test]
+    fn decimal_test() {
+        use crate::big_bcd::{BigDecimal, DecimalError, ParseError, RoundingMode};
+        let d = |s: &str| s.parse::<BigDecimal>().unwrap();
+        let x = d("-1.2345e-10");
+        assert!(x.to_string() == "-0.00000000012345" && format!("{:e}", x) == "-1.2345e-10");
+        assert!(d("1.2E+3").to_string() == "1200" && d("+.5").to_string() == "0.5");
+        assert!(format!("{:.2e}", d("12355")) == "1.24e4" && format!("{:.1E}", d("0.00")) == "0.0E-2");
+        assert!(format!("{:>8}", d("-3.10")) == "   -3.10");
+
+        let sum = d("1.50") + d("2.5");
+        assert!(sum.to_string() == "4.00" && sum == d("4") && d("1.5") == d("1.50"));
+        assert!(d("0.1") + d("0.2") == d("0.3") && d("1") - d("0.001") == d("0.999"));
+        assert!((d("1.25") * d("-0.4")).to_string() == "-0.500");
+        assert!(d("-1e-3") < d("0") && d("2.01") > d("2.009") && d("12e2") == d("1200.0"));
+        assert!(d("120.500").normalized().to_string() == "120.5");
+
+        let one = d("1");
+        let three = d("3");
+        assert!(one.divide(&three, 5, RoundingMode::HalfEven).unwrap().to_string() == "0.33333");
+        let two = d("2");
+        assert!(two.divide(&three, 5, RoundingMode::HalfEven).unwrap().to_string() == "0.66667");
+        assert!(two.divide(&three, 5, RoundingMode::Down).unwrap().to_string() == "0.66666");
+        assert!(d("-7").divide(&d("0.25"), 3, RoundingMode::HalfEven).unwrap().to_string() == "-28.0");
+        assert!(d("9.999").divide(&one, 2, RoundingMode::HalfUp).unwrap().to_string() == "10");
+        assert!(d("5e-20").divide(&d("4e10"), 2, RoundingMode::HalfEven).unwrap() == d("1.2e-30"));
+        assert!(one.divide_to_scale(&three, 2, RoundingMode::Up).unwrap().to_string() == "0.34");
+        assert!(one.divide(&d("0.0"), 5, RoundingMode::HalfEven).is_err());
+
+        let modes = [
+            (RoundingMode::HalfEven, "-2", "2", "4"),
+            (RoundingMode::HalfUp, "-3", "3", "4"),
+            (RoundingMode::HalfDown, "-2", "2", "3"),
+            (RoundingMode::Ceiling, "-2", "3", "4"),
+            (RoundingMode::Floor, "-3", "2", "3"),
+            (RoundingMode::Down, "-2", "2", "3"),
+            (RoundingMode::Up, "-3", "3", "4"),
+        ];
+        for &(mode, a, b, c) in modes.iter() {
+            assert!(d("-2.5").with_scale(0, mode).to_string() == a);
+            assert!(d("2.5").with_scale(0, mode).to_string() == b);
+            assert!(d("3.5").with_scale(0, mode).to_string() == c);
+        }
+        assert!(d("0.0001").with_scale(-5, RoundingMode::Up).to_string() == "100000");
+        assert!(d("1.5").with_scale(3, RoundingMode::Down).to_string() == "1.500");
+        assert!(d("9.96").round(2, RoundingMode::HalfEven).unwrap().to_string() == "10");
+
+        assert!("1.2.3".parse::<BigDecimal>() == Err(ParseError::InvalidDigit { position: 3, found: '.' }));
+        assert!("1e5x".parse::<BigDecimal>() == Err(ParseError::InvalidDigit { position: 3, found: 'x' }));
+        assert!("-e5".parse::<BigDecimal>() == Err(ParseError::Empty));
+        assert!("1e99999999999999999999".parse::<BigDecimal>() == Err(ParseError::ExponentOutOfRange));
+        assert!("1e 5".parse::<BigDecimal>() == Err(ParseError::InvalidDigit { position: 2, found: ' ' }));
+        assert!("1e-_5".parse::<BigDecimal>() == Err(ParseError::InvalidDigit { position: 3, found: '_' }));
+        assert!("1e+".parse::<BigDecimal>() == Err(ParseError::Empty));
+
+        let tiny = d("1e-1000000000");
+        assert!(tiny < d("1") && -&tiny > d("-1") && tiny > d("0") && tiny != -&tiny);
+        assert!(d("9.99e999999999") > d("1e999999998") && d("-5e-9") < d("-4.99e-9"));
+        assert!(d("1e-9223372036854775807") < d("1e-9223372036854775806") && d("1.0") == d("1"));
+
+        // Scales at the ends of i64.
+        let (small, large) = (d("1e-9223372036854775807"), d("12e9223372036854775807"));
+        assert!(small.multiply(&small) == Err(DecimalError::ScaleOutOfRange));
+        assert!(large.multiply(&d("1e-9223372036854775807")) == Ok(d("12")));
+        let square = d("1e4611686018427387904").multiply(&d("1e4611686018427387904")).unwrap();
+        assert!(square.scale() == i64::MIN && square.to_string() == "1e9223372036854775808");
+        assert!(format!("{:e}", large) == "1.2e9223372036854775808");
+        assert!(format!("{:.0e}", d("-95e9223372036854775807")) == "-1e9223372036854775809");
+        let third = large.divide(&d("3"), 5, RoundingMode::HalfEven);
+        assert!(third == Ok(d("4e9223372036854775807")) && third.unwrap().precision() == 5);
+        let huge = large.divide(&d("1e-10"), 5, RoundingMode::HalfEven);
+        assert!(huge == Err(DecimalError::ScaleOutOfRange));
+        let far = square.divide_to_scale(&small, i64::MAX, RoundingMode::Up);
+        assert!(far == Err(DecimalError::ScaleOutOfRange));
+        assert!(small.divide_to_scale(&d("7"), 3, RoundingMode::Up) == Ok(d("0.001")));
+        assert!(small.divide_to_scale(&d("7"), i64::MIN, RoundingMode::Down) == Ok(d("0")));
+        let hundreds = d("123e9223372036854775807");
+        assert!(hundreds.round(2, RoundingMode::HalfEven).map(|x| x.scale()) == Ok(i64::MIN));
+        assert!(hundreds.round(1, RoundingMode::HalfEven) == Err(DecimalError::ScaleOutOfRange));
+        let n = d("100e9223372036854775807").normalized();
+        assert!(n.scale() == i64::MIN && n.coefficient() == &BigBcd::from(10));
+        assert!(small.to_string() == "1e-9223372036854775807");
+        assert!(d("1.50e-2000").to_string() == "1.50e-2000");
+        assert!(d("1e1000").to_string().len() == 1001 && d("0e-5000").to_string() == "0e-5000");
+    }
+
+    #[test]
+    fn ieee_decimal_test() {
+        use crate::big_bcd::{BigDecimal, IeeeDecimal, RoundingMode};
+        use crate::big_bcd::{DecimalEncoding::*, DecimalFormat::*};
+        // DPD vectors from the General Decimal Arithmetic testcases. BID has
+        // none there, but must round-trip every value they cover.
+        let suites = [
+            (Decimal64, include_str!("../testdata/ddEncode.decTest")),
+            (Decimal128, include_str!("../testdata/dqEncode.decTest")),
+        ];
+        let hex = |s: &str| u128::from_str_radix(&s[1..], 16).unwrap();
+        for &(format, suite) in suites.iter() {
+            for line in suite.lines() {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                if words.len() < 5 || words[1] != "apply" {
+                    continue;
+                }
+                let (lhs, rhs) = (words[2], words[4]);
+                let x = if lhs.starts_with('#') {
+                    IeeeDecimal::decode(hex(lhs), format, Dpd)
+                } else {
+                    lhs.parse().unwrap()
+                };
+                let dpd = x.encode(format, Dpd, RoundingMode::HalfUp);
+                if rhs.starts_with('#') {
+                    assert!(dpd == hex(rhs), "{}", words[0]);
+                } else {
+                    assert!(x.to_string() == rhs, "{}", words[0]);
+                }
+                let y = IeeeDecimal::decode(dpd, format, Dpd);
+                let bid = y.encode(format, Bid, RoundingMode::HalfUp);
+                assert!(IeeeDecimal::decode(bid, format, Bid) == y, "{}", words[0]);
+            }
+        }
+
+        let d = |s: &str| s.parse::<IeeeDecimal>().unwrap();
+        let bid64 = |s: &str| d(s).encode(Decimal64, Bid, RoundingMode::HalfEven);
+        assert!(bid64("1") == 0x31C0000000000001 && bid64("-7.50") == 0xB1800000000002EE);
+        assert!(bid64("9.999999999999999E+384") == 0x77FB86F26FC0FFFF);
+        let max128 = d("9.999999999999999999999999999999999E+6144");
+        let max_bid = max128.encode(Decimal128, Bid, RoundingMode::HalfEven);
+        assert!(max_bid == 0x5FFFED09BEAD87C0378D8E63FFFFFFFF);
+        assert!(IeeeDecimal::decode(0x6C7386F26FC10000, Decimal64, Bid) == d("0"));
+
+        let round64 = |s: &str, mode| d(s).rounded(Decimal64, mode).to_string();
+        assert!(round64("12345678901234567", RoundingMode::HalfEven) == "1.234567890123457E+16");
+        assert!(round64("9999999999999999.5", RoundingMode::HalfEven) == "1.000000000000000E+16");
+        assert!(round64("9999999999999999.5", RoundingMode::Down) == "9999999999999999");
+        assert!(round64("1.5E-398", RoundingMode::HalfEven) == "2E-398");
+        assert!(round64("1.5E-398", RoundingMode::Down) == "1E-398");
+        assert!(round64("-4E-399", RoundingMode::HalfEven) == "-0E-398");
+        assert!(round64("1E+385", RoundingMode::HalfEven) == "Infinity");
+        assert!(round64("1E+385", RoundingMode::Down) == "9.999999999999999E+384");
+        assert!(round64("-1E+385", RoundingMode::Floor) == "-Infinity");
+        assert!(round64("-1E+385", RoundingMode::Ceiling) == "-9.999999999999999E+384");
+
+        let (a, b) = (d("1.0"), d("1.00"));
+        assert!(a != b && a.to_decimal() == b.to_decimal());
+        let dpd64 = |x: &IeeeDecimal| x.encode(Decimal64, Dpd, RoundingMode::HalfEven);
+        assert!(dpd64(&a) != dpd64(&b));
+        assert!(d("-0").to_decimal() == d("0").to_decimal() && d("-0") != d("0"));
+        assert!(d("sNaN").to_decimal().is_none() && d("-inf").to_string() == "-Infinity");
+        assert!("NaN-5".parse::<IeeeDecimal>().is_err());
+        assert!("--inf".parse::<IeeeDecimal>().is_err() && "+-NaN".parse::<IeeeDecimal>().is_err());
+        let huge = d("1e9223372036854775807");
+        assert!(huge.to_string() == "1E+9223372036854775807");
+        assert!(huge.rounded(Decimal64, RoundingMode::HalfEven) == d("inf"));
+        assert!(huge.rounded(Decimal64, RoundingMode::Down).to_string() == "9.999999999999999E+384");
+        assert!(d("-1e-9223372036854775807").rounded(Decimal128, RoundingMode::Floor) == d("-1E-6176"));
+        let square = BigDecimal::new(BigBcd::from(-3), i64::MIN);
+        let finite = IeeeDecimal::Finite { neg: true, coeff: BigBcd::from(30), exp: i64::MAX };
+        assert!(IeeeDecimal::from(&square) == finite && finite.to_decimal() == Some(square));
+        let tiny = IeeeDecimal::Finite { neg: false, coeff: BigBcd::from(1), exp: i64::MIN };
+        assert!(tiny.to_decimal().is_none());
+    }
+
+    #[test]
+    fn digits_test() {
+        let x = big("-1234567890123");
+        let le: Vec<u8> = x.digits_le().collect();
+        assert!(le == vec![3, 2, 1, 0, 9, 8, 7, 6, 5, 4, 3, 2, 1] && x.digits_le().len() == 13);
+        assert!(x.digits_be().take(3).collect::<Vec<u8>>() == vec![1, 2, 3]);
+        assert!(BigBcd::from(0).digits_be().collect::<Vec<u8>>() == vec![0]);
+        assert!(x.num_digits() == 13 && x.digit(9) == 4 && x.digit(40) == 0);
+
+        let mut y = BigBcd::from(5);
+        y.set_digit(20, 7);
+        assert!(y == big("700000000000000000005"));
+        y.set_digit(20, 0);
+        y.set_digit(0, 0);
+        assert!(y == BigBcd::from(0) && !y.is_negative());
+        let mut z = BigBcd::from(-19);
+        z.set_digit(1, 4);
+        assert!(z == BigBcd::from(-49));
+
+        assert!(x.digit_sum() == 51 && x.digital_root() == 6);
+        assert!(BigBcd::from(0).digital_root() == 0 && big("999999999999").digital_root() == 9);
+        assert!(BigBcd::from(-1200).reverse_digits() == BigBcd::from(-21));
+        assert!(big("12345678987654321").is_palindrome() && !BigBcd::from(10).is_palindrome());
+        assert!(BigBcd::from(12345).rotate_digits_left(1) == BigBcd::from(23451));
+        assert!(BigBcd::from(12345).rotate_digits_right(7) == BigBcd::from(45123));
+        assert!(BigBcd::from(1023).rotate_digits_left(1) == BigBcd::from(231));
+
+        let perms: Vec<BigBcd> = BigBcd::from(1021).digit_permutations().collect();
+        assert!(perms.len() == 12 && perms[0] == BigBcd::from(112) && perms[11] == BigBcd::from(2110));
+        assert!(perms.windows(2).all(|w| w[0] < w[1]));
+        assert!(BigBcd::from(-7).digit_permutations().collect::<Vec<_>>() == vec![BigBcd::from(-7)]);
+
+        for k in [0, 1, 8, 9, 10, 27, 100] {
+            let p = BigBcd::from(10).pow(k as u32);
+            assert!(x.shl10(k) == x.multiply(&p) && x.shl10(k).shr10(k) == x);
+            assert!(x.shr10(k) == x.divide(&p).unwrap());
+        }
+        assert!(BigBcd::from(0).shl10(30) == BigBcd::from(0));
+        assert!(BigBcd::from(-999).shr10(3) == BigBcd::from(0));
+    }
+
+    #[test]
+    fn comb_test() {
+        let naive = |n: usize| (1..=n).fold(BigBcd::from(1), |acc, i| acc * i);
+        for &n in [0, 1, 2, 20, 21, 100, 333].iter() {
+            assert!(BigBcd::factorial(n) == naive(n));
+        }
+        let f = BigBcd::factorial(1000);
+        assert!(f.num_digits() == 2568 && f.digit_sum() == 10539);
+        assert!(f.to_string().ends_with(&"0".repeat(249)));
+
+        assert!(BigBcd::binomial(10, 3) == BigBcd::from(120));
+        assert!(BigBcd::binomial(3, 10) == BigBcd::from(0));
+        assert!(BigBcd::binomial(0, 0) == BigBcd::from(1) && BigBcd::binomial(7, 7) == BigBcd::from(1));
+        let c = BigBcd::binomial(2000, 1000);
+        assert!(c == BigBcd::factorial(2000) / (BigBcd::factorial(1000) * BigBcd::factorial(1000)));
+        assert!(BigBcd::binomial(100, 50) == big("100891344545564193334812497256"));
+
+        assert!(BigBcd::multinomial(&[2, 3, 4]) == BigBcd::from(1260));
+        assert!(BigBcd::multinomial(&[]) == BigBcd::from(1));
+        assert!(BigBcd::multinomial(&[5]) == BigBcd::from(1));
+        assert!(BigBcd::multinomial(&[60, 40]) == BigBcd::binomial(100, 40));
+
+        let catalan: Vec<BigBcd> = (0..8).map(BigBcd::catalan).collect();
+        let expected: Vec<BigBcd> =
+            [1, 1, 2, 5, 14, 42, 132, 429].iter().map(|&x| BigBcd::from(x)).collect();
+        assert!(catalan == expected);
+
+        let (mut a, mut b) = (BigBcd::from(0), BigBcd::from(1));
+        for n in 0..300 {
+            assert!(BigBcd::fibonacci(n) == a);
+            b += &a;
+            std::mem::swap(&mut a, &mut b);
+        }
+        assert!(BigBcd::fibonacci(100) == big("354224848179261915075"));
+        assert!(BigBcd::lucas(0) == BigBcd::from(2) && BigBcd::lucas(1) == BigBcd::from(1));
+        assert!(BigBcd::lucas(50) == big("28143753123"));
+        assert!(BigBcd::lucas(1000) == BigBcd::fibonacci(999) + BigBcd::fibonacci(1001));
+    }
+
+    #[test]
+    fn constants_test() {
+        use crate::big_bcd::constants;
+        // 1000 places, checked against Python's decimal module: the leading
+        // digits, the trailing digits and the digit sum.
+        let pi: fn(usize) -> BigBcd = constants::pi;
+        let cases = [
+            (pi, "3141592653589793238462643383279502884197169399375105820974944",
+                "130019278766111959092164201989", 4479),
+            (constants::e, "2718281828459045235360287471352662497757247093699959574966967",
+                "873969655212671546889570350354", 4563),
+            (constants::ln2, "693147180559945309417232121458176568075500134360255254120680",
+                "053401649256872747782344535347", 4425),
+            (constants::sqrt2, "1414213562373095048801688724209698078569671875376948073176679",
+                "419758716582152128229518488472", 4483),
+            (constants::phi, "1618033988749894848204586834365638117720309179805762862135448",
+                "267575605231727775203536139362", 4482),
+        ];
+        for &(f, head, tail, digit_sum) in cases.iter() {
+            let x = f(1000).to_string();
+            assert!(x.starts_with(head) && x.ends_with(tail));
+            assert!(f(1000).digit_sum() == digit_sum);
+        }
+        assert!(constants::pi(5) == BigBcd::from(314159) && constants::ln2(3) == BigBcd::from(693));
+        let zero_places: Vec<BigBcd> = cases.iter().map(|c| c.0(0)).collect();
+        assert!(zero_places == [3, 2, 0, 1, 1].iter().map(|&x| BigBcd::from(x)).collect::<Vec<_>>());
+        assert!(constants::pi(6000).shr10(5000) == constants::pi(1000));
+        assert!(constants::e(6000).shr10(5000) == constants::e(1000));
+    }
+
+    #[test]
+    fn bits_test() {
+        let mut seed = 88172645463325252u64;
+        let mut vals: Vec<i128> =
+            vec![0, 1, -1, 2, -2, 255, -256, i64::MAX as i128, i64::MIN as i128];
+        for _ in 0..40 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            vals.push((seed as i64 >> (seed % 60)) as i128);
+        }
+        for &a in vals.iter() {
+            let x = BigBcd::from(a);
+            for &b in vals.iter() {
+                let y = BigBcd::from(b);
+                assert!(&x & &y == BigBcd::from(a & b) && &x | &y == BigBcd::from(a | b));
+                assert!(&x ^ &y == BigBcd::from(a ^ b));
+            }
+            assert!(!&x == BigBcd::from(!a) && x.clone() & 0xff == BigBcd::from(a & 0xff));
+            for k in [0, 1, 5, 31, 32, 33, 63] {
+                assert!(&x << k == BigBcd::from(a << k) && &x >> k == BigBcd::from(a >> k));
+                assert!(x.test_bit(k) == ((a >> k) & 1 == 1));
+                for v in [false, true] {
+                    let mut y = x.clone();
+                    y.set_bit(k, v);
+                    let expected = if v { a | 1 << k } else { a & !(1 << k) };
+                    assert!(y == BigBcd::from(expected));
+                }
+            }
+            assert!(x.bit_length() == (128 - a.unsigned_abs().leading_zeros()) as u64);
+            assert!(x.count_ones() == a.unsigned_abs().count_ones() as u64);
+            let tz = if a == 0 { None } else { Some(a.trailing_zeros() as u64) };
+            assert!(x.trailing_zeros() == tz);
+        }
+
+        let x = BigBcd::from(3).pow(500);
+        let y = -BigBcd::from(7).pow(300);
+        assert!(&x & &y == &x - (&x & (-&y - 1)) && &x ^ &y == (&x | &y) - (&x & &y));
+        assert!(&y & -&y == BigBcd::from(1) << y.trailing_zeros().unwrap() as usize);
+        assert!(x.bit_length() == 793 && (&x >> 792) == BigBcd::from(1) && (&x >> 793).is_zero());
+        assert!((&y << 1000) >> 1000 == y && (&y >> 1000) == BigBcd::from(-1));
+        assert!(y.test_bit(5000) && !x.test_bit(5000));
+        let mut z = BigBcd::from(0);
+        z.set_bit(300, true);
+        assert!(z == BigBcd::from(2).pow(300) && z.count_ones() == 1);
+
+        let huge = u32::MAX as usize + 5;
+        assert!((&x >> huge).is_zero() && (&y >> huge) == BigBcd::from(-1));
+        assert!(BigBcd::from(0).shl2(huge).is_zero() && y.test_bit(huge) && !x.test_bit(huge));
+        z = BigBcd::from(-1);
+        z.set_bit(huge, true);
+        assert!(z == BigBcd::from(-1));
+    }
+
+    #[test]
+    fn std_traits_test() {
+        use crate::big_bcd::BigDecimal;
+        use std::collections::{HashMap, HashSet};
+
+        let set: HashSet<BigBcd> = ["0", "-0", "+0", "007", "7", "-7", "1000000000"]
+            .iter()
+            .map(|&s| big(s))
+            .collect();
+        assert!(set.len() == 4 && set.contains(&BigBcd::from(7)));
+        assert!(set.contains(&(BigBcd::from(3) - 3)));
+        let mut memo: HashMap<BigBcd, u32> = HashMap::new();
+        memo.insert(BigBcd::from(2).pow(100), 1);
+        assert!(memo.get(&big("1267650600228229401496703205376")) == Some(&1));
+
+        let decimals: HashSet<BigDecimal> = ["1.5", "1.50", "15e-1", "0", "0.000", "-0.0", "150"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert!(decimals.len() == 3);
+
+        assert!(BigBcd::default() == BigBcd::from(0));
+        let xs: Vec<BigBcd> = (1..=30).map(BigBcd::from).collect();
+        assert!(xs.iter().sum::<BigBcd>() == BigBcd::from(465));
+        assert!(xs.iter().product::<BigBcd>() == BigBcd::factorial(30));
+        assert!(xs.into_iter().map(|x| -x).sum::<BigBcd>() == BigBcd::from(-465));
+        assert!(std::iter::empty::<BigBcd>().product::<BigBcd>() == BigBcd::from(1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+        let x = big("-1234567890123456789012345");
+        assert_tokens(&x.readable(), &[Token::Str("-1234567890123456789012345")]);
+        assert_tokens(&BigBcd::from(0).readable(), &[Token::Str("0")]);
+        assert_tokens(&BigBcd::from(258).compact(), &[Token::Bytes(&[0, 2, 1])]);
+        assert_tokens(&BigBcd::from(-1).compact(), &[Token::Bytes(&[1, 1])]);
+        assert_tokens(&BigBcd::from(0).compact(), &[Token::Bytes(&[0, 0])]);
+
+        let y = BigBcd::from(-(1i128 << 64) - 5);
+        assert_tokens(&y.compact(), &[Token::Bytes(&[1, 5, 0, 0, 0, 0, 0, 0, 0, 1])]);
+
+        assert_de_tokens_error::<serde_test::Readable<BigBcd>>(
+            &[Token::Str("12a")],
+            "invalid digit 'a' at position 2 in BigBcd",
+        );
+        assert_de_tokens_error::<serde_test::Compact<BigBcd>>(
+            &[Token::Bytes(&[2, 1])],
+            concat!(
+                "invalid value: byte array, expected a decimal integer string",
+                " or a sign byte and magnitude bytes"
+            ),
+        );
+    }
+
+    #[test]
+    fn canonical_test() {
+        assert!(big("0010") == BigBcd::from(10) && big("007") == BigBcd::from(7));
+        assert!(big("-0") == BigBcd::from(0));
+        assert!(big("000000000000000000") == BigBcd::from(0));
+        assert!("".parse::<BigBcd>().is_err() && "-".parse::<BigBcd>().is_err());
+        let zeros = [
+            BigBcd::from(-5) + 5,
+            BigBcd::from(0) * -3,
+            BigBcd::from(7) / BigBcd::from(-8),
+            BigBcd::from(-16) % BigBcd::from(4),
+            big("1000000000000000000") - big("1000000000000000000"),
+            BigBcd::from_bytes_be(&[0, 0, 0, 0, 0, 0]),
+            BigBcd::from_str_radix("-0000", 16).unwrap(),
+        ];
+        assert!(zeros.iter().all(|z| z.is_canonical() && z.is_zero() && !z.is_negative()));
+
+        let mut seed = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        // Random magnitudes of every size up to 126 bits, so sums stay in range.
+        let mut vals: Vec<u128> =
+            vec![0, 1, 999_999_999, 1_000_000_000, 10u128.pow(18), 10u128.pow(27)];
+        for _ in 0..200 {
+            let x = (next() as u128) << 64 | next() as u128;
+            vals.push(x >> (2 + next() % 126));
+        }
+        for &a in vals.iter() {
+            let x = BigBcd::from(a);
+            let padded = big(&format!("+000{}", a));
+            let rebuilt = BigBcd::from(a + 12345) - 12345;
+            let p = BigBcd::from(10u128.pow(20));
+            let scaled = &x * &p / &p;
+            let bytes = BigBcd::from_bytes_le(&a.to_le_bytes());
+            for y in [&padded, &rebuilt, &scaled, &bytes] {
+                assert!(y.is_canonical() && *y == x && y.cmp(&x) == std::cmp::Ordering::Equal);
+            }
+            assert!((-&x).is_canonical() && (&x - &x).is_canonical());
+            for &b in vals.iter() {
+                let y = BigBcd::from(b);
+                assert!((x == y) == (a == b) && x.cmp(&y) == a.cmp(&b));
+                assert!((-&x).cmp(&-&y) == b.cmp(&a) && (-&x <= y));
+                let sum = &x + &y;
+                assert!(sum.is_canonical() && sum == BigBcd::from(a + b));
+                let diff = &x - &y;
+                assert!(diff.is_canonical() && diff.cmp(&BigBcd::from(0)) == a.cmp(&b));
+                assert!(diff.abs() == BigBcd::from(a.max(b) - a.min(b)));
+                if let Some(quot) = a.checked_div(b) {
+                    let (q, r) = x.div_rem(&y).unwrap();
+                    assert!(q.is_canonical() && r.is_canonical());
+                    assert!(q == BigBcd::from(quot) && r == BigBcd::from(a % b));
+                }
+                if let Some(p) = a.checked_mul(b) {
+                    assert!((&x * &y) == BigBcd::from(p));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn format_test() {
+        use crate::big_bcd::Grouping;
+
+        let x = BigBcd::from(-1234567);
+        assert!(format!("{:>12}|{:<12}|{:^12}", x, x, x) == "    -1234567|-1234567    |  -1234567  ");
+        assert!(format!("{:*^11}", BigBcd::from(42)) == "****42*****");
+        assert!(format!("{:+}|{:+}|{:+}", BigBcd::from(5), x, BigBcd::from(0)) == "+5|-1234567|+0");
+        assert!(format!("{:010}|{:+06}", x, BigBcd::from(42)) == "-001234567|+00042");
+        let grouped = format!("{:#}|{:#}|{:#}", x, BigBcd::from(999), BigBcd::from(1000));
+        assert!(grouped == "-1,234,567|999|1,000");
+        assert!(format!("{:>#12}", BigBcd::from(10).pow(9)) == "1,000,000,000");
+        assert!(format!("{:>#14}", BigBcd::from(10).pow(9)) == " 1,000,000,000");
+
+        for &n in [0i64, 1, -1, 9, 10, 1200, -1234500, 999_999_999, 1_000_000_000, i64::MIN].iter() {
+            let x = BigBcd::from(n);
+            assert!(format!("{:e}", x) == format!("{:e}", n));
+            assert!(format!("{:E}", x) == format!("{:E}", n));
+            assert!(format!("{:>+20e}", x) == format!("{:>+20e}", n));
+        }
+        let x = BigBcd::from(123456789);
+        assert!(format!("{:.0e}|{:.3e}|{:.3E}", x, x, -&x) == "1e8|1.235e8|-1.235E8");
+        assert!(format!("{:.2e}|{:.2e}", BigBcd::from(1225), BigBcd::from(1235)) == "1.22e3|1.24e3");
+        assert!(format!("{:.2e}|{:.4e}", BigBcd::from(9999), BigBcd::from(7)) == "1.00e4|7.0000e0");
+
+        let big = BigBcd::from(7).pow(60000);
+        let (s, e) = (big.to_string(), format!("{:.5e}", big));
+        assert!(s.len() == 50706 && e.starts_with(&s[..1]) && e.ends_with("e50705") && e.len() == 13);
+        let mantissa: f64 = e[..7].parse().unwrap();
+        let expected: f64 = format!("{}.{}", &s[..1], &s[1..10]).parse().unwrap();
+        assert!((mantissa - expected).abs() <= 0.000005);
+        assert!(format!("{:#}", big).len() == 50706 + 50705 / 3);
+
+        let x = BigBcd::from(-12345678);
+        assert!(x.to_grouped_string(",", Grouping::Thousands) == "-12,345,678");
+        assert!(x.to_grouped_string(",", Grouping::Indian) == "-1,23,45,678");
+        assert!(BigBcd::from(123456789).to_grouped_string("_", Grouping::Uniform(4)) == "1_2345_6789");
+        assert!(BigBcd::from(1000).to_grouped_string("\u{202f}", Grouping::Thousands) == "1\u{202f}000");
+        assert!(BigBcd::from(999).to_grouped_string(",", Grouping::Indian) == "999");
+        assert!(BigBcd::from(0).to_grouped_string(".", Grouping::Thousands) == "0");
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_test() {
+        use crate::big_bcd::{self, constants};
+
+        let run = || {
+            let x = BigBcd::from(3).pow(60000);
+            let y = BigBcd::from(7).pow(20000) + 1;
+            (
+                BigBcd::factorial(20000),
+                BigBcd::fibonacci(100000),
+                &x * &y,
+                &x * &(&x - 1),
+                &x * &(BigBcd::from(11).pow(8700) - 1),
+                BigBcd::from_str_radix(&x.to_str_radix(16), 16).unwrap() - &x,
+                constants::e(12000),
+            )
+        };
+        big_bcd::set_threads(1);
+        assert!(big_bcd::threads() == 1);
+        let serial = run();
+        big_bcd::set_threads(4);
+        assert!(big_bcd::threads() == 4 && run() == serial);
+        big_bcd::set_threads(0);
+        assert!(big_bcd::threads() >= 1 && run() == serial);
+        assert!(serial.5.is_zero() && serial.0.num_digits() == 77338);
+    }
+
+    #[test]
+    fn random_test() {
+        use crate::big_bcd::{RandomSource, XorShiftRng};
+        use std::convert::TryFrom;
+
+        let (mut a, mut b) = (XorShiftRng::new(42), XorShiftRng::new(42));
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+        assert!(XorShiftRng::new(0).next_u64() != XorShiftRng::new(1).next_u64());
+        assert!(XorShiftRng::new(7).seed() == 7);
+
+        // Whatever seed from_entropy picks, it replays through new.
+        let mut fresh = XorShiftRng::from_entropy();
+        assert!(XorShiftRng::new(fresh.seed()).next_u64() == fresh.next_u64());
+
+        // A fixed seed, so the statistical checks below pass or fail the same
+        // way on every run.
+        let mut rng = XorShiftRng::new(20250214);
+
+        let mut counts = [0; 10];
+        for _ in 0..10000 {
+            let x = BigBcd::random_below(&BigBcd::from(10), &mut rng);
+            counts[u64::try_from(&x).unwrap() as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (800..1200).contains(&c)));
+        assert!(BigBcd::random_below(&BigBcd::from(1), &mut rng).is_zero());
+        // Just over a limb boundary, where half the candidates are rejected.
+        let bound = BigBcd::from(1_000_000_001);
+        let mut above_half = 0;
+        for _ in 0..2000 {
+            let x = BigBcd::random_below(&bound, &mut rng);
+            assert!(x.is_canonical() && !x.is_negative() && x < bound);
+            above_half += (x > BigBcd::from(500_000_000)) as i32;
+        }
+        assert!((850..1150).contains(&above_half));
+        let bound = BigBcd::from(3).pow(1000);
+        assert!((0..20).all(|_| BigBcd::random_below(&bound, &mut rng) < bound));
+
+        for n in 1..60 {
+            let x = BigBcd::random_with_digits(n, &mut rng);
+            assert!(x.num_digits() == n && x.is_positive());
+        }
+        let small: std::collections::HashSet<BigBcd> =
+            (0..500).map(|_| BigBcd::random_with_digits(1, &mut rng)).collect();
+        assert!(small.len() == 9 && !small.contains(&BigBcd::from(0)));
+
+        let primes: std::collections::HashSet<usize> = sieve(20000).into_iter().collect();
+        for n in 0..20000 {
+            assert!(BigBcd::from(n).is_probable_prime(8, &mut rng) == primes.contains(&n));
+        }
+        for &carmichael in ["561", "41041", "3215031751", "3825123056546413051"].iter() {
+            assert!(!big(carmichael).is_probable_prime(16, &mut rng));
+        }
+        let mut mersenne = |p: u32| {
+            let m = BigBcd::from(2).pow(p).subtract(&BigBcd::from(1));
+            m.is_probable_prime(16, &mut rng)
+        };
+        assert!(mersenne(127) && mersenne(521) && !mersenne(67) && !mersenne(523));
+        assert!(!BigBcd::from(-7).is_probable_prime(16, &mut rng));
+
+        for &digits in [1, 2, 20, 60].iter() {
+            let p = BigBcd::random_prime(digits, &mut rng);
+            assert!(p.num_digits() == digits && p.is_probable_prime(32, &mut rng));
+        }
+        let replay = |seed| BigBcd::random_prime(40, &mut XorShiftRng::new(seed));
+        assert!(replay(2024) == replay(2024) && replay(2024) != replay(2025));
+
+        #[cfg(feature = "rand")]
+        {
+            use rand::SeedableRng;
+            let mut small = rand::rngs::SmallRng::seed_from_u64(5);
+            let x = BigBcd::random_below(&BigBcd::from(10).pow(30), &mut small);
+            assert!(x.num_digits() <= 30 && BigBcd::random_prime(10, &mut small).num_digits() == 10);
+        }
+    }
+
+    #[test]
+    fn ratio_gcd_clone_test() {
+        // BigBcd is Clone but not Copy.
+        let g = crate::ratio::gcd(BigBcd::from(84), BigBcd::from(36));
+        assert!(g == BigBcd::from(12));
+        assert!(crate::ratio::gcd(BigBcd::from(0), BigBcd::from(5)) == BigBcd::from(5));
+        assert!(crate::ratio::gcd(BigBcd::from(7), BigBcd::from(0)) == BigBcd::from(7));
+        assert!(crate::ratio::gcd(17u32, 5u32) == 1);
+        let r = Ratio::new(BigBcd::from(1071), BigBcd::from(462));
+        assert!(r.numerator == BigBcd::from(51) && r.denominator == BigBcd::from(22));
+        // Far too many steps for repeated subtraction.
+        let big = BigBcd::from(10).pow(40);
+        assert!(crate::ratio::gcd(big.clone(), BigBcd::from(1)) == BigBcd::from(1));
+        assert!(crate::ratio::gcd(&big * 6, &big * 4) == big * 2);
+    }
+
+    #[test]
+    fn ratio_big_bcd_test() {
+        let mut sum = Ratio::new(BigBcd::from(0), BigBcd::from(1));
+        for i in 1..=30usize {
+            sum = sum + Ratio::new(BigBcd::from(1), BigBcd::from(i));
+        }
+        assert!(sum.numerator == big("9304682830147"));
+        assert!(sum.denominator == big("2329089562800"));
+        let a = Ratio::new(big("-6"), BigBcd::from(4));
+        assert!(a.numerator == big("-3") && a.denominator == BigBcd::from(2));
+        assert!(a < Ratio::new(BigBcd::from(1), BigBcd::from(3)));
+    }
+
+    // Deterministic pseudo-random digit strings for cross-checking algorithms.
+    fn random_digits(state: &mut u64, len: usize) -> String {
+        let mut s = String::with_capacity(len);
+        for _ in 0..len {
+            *state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            s.push((b'0' + ((*state >> 33) % 10) as u8) as char);
+        }
+        s
+    }
+
+    #[test]
+    fn fast_mul_test() {
+        let mut state = 12345;
+        let mut lens: Vec<usize> = (1..60).collect();
+        lens.extend([287, 288, 289, 400, 577, 1151, 1152, 1153, 1500, 2500].iter());
+        for &n in lens.iter() {
+            for &m in [1, n / 3 + 1, n / 2 + 1, n - n / 4, n, n + 7].iter() {
+                let a = big(&random_digits(&mut state, n));
+                let b = big(&random_digits(&mut state, m)).negated();
+                let expected = a.multiply_schoolbook(&b);
+                assert!(a.multiply_karatsuba(&b) == expected);
+                assert!(a.multiply_toom3(&b) == expected);
+                assert!(a.multiply(&b) == expected);
+                assert!(b.multiply(&a) == expected);
+            }
+        }
+        let nines = big(&"9".repeat(700));
+        let expected = big(&format!("{}8{}1", "9".repeat(699), "0".repeat(699)));
+        assert!(nines.multiply(&nines) == expected);
+        assert!(nines.multiply_toom3(&nines) == expected);
+    }
+
+    #[test]
+    fn ntt_mul_test() {
+        let mut state = 54321;
+        for &n in [1, 2, 8, 9, 10, 17, 100, 499, 500, 501, 1234, 3000].iter() {
+            for &m in [1, n / 2 + 1, n, n + 9].iter() {
+                let a = big(&random_digits(&mut state, n));
+                let b = big(&random_digits(&mut state, m));
+                let expected = a.multiply_karatsuba(&b);
+                assert!(a.multiply_ntt(&b) == expected);
+                assert!(a.multiply(&b) == expected);
+            }
+        }
+        let a = big(&random_digits(&mut state, 20000));
+        let b = big(&random_digits(&mut state, 30000));
+        assert!(a.multiply_ntt(&b) == a.multiply_toom3(&b));
+        let n = 200000;
+        let nines = big(&"9".repeat(n));
+        let expected = big(&format!("{}8{}1", "9".repeat(n - 1), "0".repeat(n - 1)));
+        assert!(nines.multiply(&nines) == expected);
+    }
+
+    #[test]
+    fn limb_digits_test() {
+        let a = big("-1000000000200000000030000000004");
+        assert!(format!("{}", a) == "-1000000000200000000030000000004");
+        assert!(a.num_digits() == 31);
+        assert!(a.digit(0) == 4 && a.digit(9) == 0 && a.digit(10) == 3);
+        assert!(a.digit(30) == 1 && a.digit(31) == 0 && a.digit(1000) == 0);
+        assert!(BigBcd::from(0).num_digits() == 1);
+        assert!(BigBcd::from(999999999).num_digits() == 9);
+        assert!(BigBcd::from(1000000000).num_digits() == 10);
+        assert!(format!("{}", BigBcd::from(1000000000)) == "1000000000");
+        let b = big("999999999999999999");
+        assert!(b.add(&BigBcd::from(1)) == big("1000000000000000000"));
+        assert!(big("1000000000000000000").subtract(&BigBcd::from(1)) == b);
+        let (q, r) = big("1000000000000000000000000000").div_rem(&big("999999999000000001")).unwrap();
+        assert!(q == big("1000000000"));
+        assert!(r == big("999999999000000000"));
+    }
+
+    #[test]
+    fn packed_bcd_test() {
+        use crate::big_bcd::{BcdError, ByteOrder};
+        let a = BigBcd::from(12345);
+        assert!(a.to_packed_bcd(ByteOrder::BigEndian) == Ok(vec![0x01, 0x23, 0x45]));
+        assert!(a.to_packed_bcd(ByteOrder::LittleEndian) == Ok(vec![0x45, 0x23, 0x01]));
+        assert!(a.to_packed_bcd_bytes(ByteOrder::BigEndian, Some(4)) == Ok(vec![0, 0x01, 0x23, 0x45]));
+        assert!(a.to_packed_bcd_bytes(ByteOrder::BigEndian, Some(2)).is_err());
+        assert!(big("-1").to_packed_bcd(ByteOrder::BigEndian) == Err(BcdError::Negative));
+        assert!(BigBcd::from_packed_bcd(&[0x45, 0x23, 0x01], ByteOrder::LittleEndian) == Ok(a.clone()));
+        assert!(
+            BigBcd::from_packed_bcd(&[0x12, 0x3a], ByteOrder::BigEndian)
+                == Err(BcdError::InvalidNibble { position: 1, nibble: 0xa })
+        );
+
+        assert!(a.to_comp3() == vec![0x12, 0x34, 0x5c]);
+        assert!(big("-1234").to_comp3() == vec![0x01, 0x23, 0x4d]);
+        assert!(big("-1234").to_comp3_digits(Some(7)) == Ok(vec![0x00, 0x01, 0x23, 0x4d]));
+        assert!(BigBcd::from_comp3(&[0x01, 0x23, 0x4d]) == Ok(big("-1234")));
+        assert!(BigBcd::from_comp3(&[0x12, 0x34, 0x5f]) == Ok(a.clone()));
+        assert!(BigBcd::from_comp3(&[0x12, 0x34, 0x59]) == Err(BcdError::InvalidSign { nibble: 9 }));
+        assert!(BigBcd::from_comp3(&[]) == Err(BcdError::Empty));
+
+        assert!(big("-123").to_zoned() == vec![0xf1, 0xf2, 0xd3]);
+        assert!(BigBcd::from_zoned(&[0xf1, 0xf2, 0xc3]) == Ok(BigBcd::from(123)));
+        assert!(
+            BigBcd::from_zoned(&[0xf1, 0x32, 0xc3])
+                == Err(BcdError::InvalidZone { position: 1, zone: 3 })
+        );
+
+        let mut state = 777;
+        for len in 1..60 {
+            let x = big(&random_digits(&mut state, len));
+            let y = x.negated();
+            for &order in [ByteOrder::BigEndian, ByteOrder::LittleEndian].iter() {
+                let bytes = x.to_packed_bcd(order).unwrap();
+                assert!(BigBcd::from_packed_bcd(&bytes, order) == Ok(x.clone()));
+            }
+            assert!(BigBcd::from_comp3(&y.to_comp3()) == Ok(y.clone()));
+            assert!(BigBcd::from_zoned(&y.to_zoned()) == Ok(y.clone()));
+            assert!(BigBcd::from_zoned(&x.to_zoned_digits(Some(70)).unwrap()) == Ok(x.clone()));
+        }
+    }
+
+    #[test]
+    fn parse_test() {
+        use crate::big_bcd::ParseError;
+        use std::convert::TryFrom;
+        assert!("  -0001_234_567 \n".parse() == Ok(big("-1234567")));
+        assert!("+000".parse::<BigBcd>() == Ok(BigBcd::from(0)));
+        assert!(!"-0".parse::<BigBcd>().unwrap().is_negative());
+        assert!("0012".parse::<BigBcd>().unwrap() > BigBcd::from(9));
+        assert!("".parse::<BigBcd>() == Err(ParseError::Empty));
+        assert!("  - ".parse::<BigBcd>() == Err(ParseError::Empty));
+        assert!(
+            "12a4".parse::<BigBcd>()
+                == Err(ParseError::InvalidDigit { position: 2, found: 'a' })
+        );
+        assert!(
+            " 1 2".parse::<BigBcd>()
+                == Err(ParseError::InvalidDigit { position: 2, found: ' ' })
+        );
+        assert!(
+            "-_1".parse::<BigBcd>()
+                == Err(ParseError::InvalidDigit { position: 1, found: '_' })
+        );
+        assert!(
+            "1_".parse::<BigBcd>()
+                == Err(ParseError::InvalidDigit { position: 1, found: '_' })
+        );
+        assert!(
+            "1__2".parse::<BigBcd>()
+                == Err(ParseError::InvalidDigit { position: 2, found: '_' })
+        );
+        assert!(
+            "1é".parse::<BigBcd>()
+                == Err(ParseError::InvalidDigit { position: 1, found: 'é' })
+        );
+        assert!(BigBcd::try_from("1_2") == Ok(BigBcd::from(12)));
+        assert!(BigBcd::try_from("12x") == Err(ParseError::InvalidDigit { position: 2, found: 'x' }));
+        assert!(BigBcd::from_str_radix(" -f_f ", 16) == Ok(big("-255")));
+        assert!(BigBcd::from_str_radix("1", 37) == Err(ParseError::InvalidRadix(37)));
+
+        let mut state = 99;
+        for len in 1..40 {
+            let s = format!("1{}", random_digits(&mut state, len));
+            assert!(s.parse::<BigBcd>().unwrap().to_string() == s);
+            assert!(format!("00{}", s).parse::<BigBcd>().unwrap().to_string() == s);
+        }
+    }
+
+    #[test]
+    fn fast_div_test() {
+        use crate::big_bcd::DivisionByZero;
+        let check = |a: &BigBcd, b: &BigBcd| {
+            let (q, r) = a.div_rem(b).unwrap();
+            assert!(!r.is_negative() && r < *b);
+            assert!(q.multiply(b).add(&r) == *a);
+        };
+
+        let mut state = 4242;
+        let lens = [1, 2, 9, 10, 18, 19, 40, 100, 500, 1801, 2500, 4000, 9000];
+        for &n in lens.iter() {
+            for &m in lens.iter() {
+                let a = big(&format!("1{}", random_digits(&mut state, n)));
+                let b = big(&format!("1{}", random_digits(&mut state, m)));
+                check(&a, &b);
+                check(&a.multiply(&b).add(&b.subtract(&BigBcd::from(1))), &b);
+            }
+        }
+
+        // Divisors whose leading limbs make the quotient estimates overshoot.
+        for &k in [2, 3, 30, 250].iter() {
+            let nines = big(&"9".repeat(9 * k));
+            let b = nines.subtract(&BigBcd::from(1).add(&big(&"1".repeat(9 * k - 5))));
+            check(&nines.multiply(&nines).multiply(&nines), &b);
+            check(&nines.multiply(&nines).multiply(&nines), &big(&format!("5{}", "0".repeat(9 * k))));
+        }
+
+        // Large enough for Newton division.
+        let a = big(&format!("1{}", random_digits(&mut state, 45000)));
+        let b = big(&format!("1{}", random_digits(&mut state, 20000)));
+        check(&a, &b);
+        check(&a.multiply(&b), &b);
+
+        assert!(BigBcd::from(5).div_rem(&BigBcd::from(0)) == Err(DivisionByZero));
+        assert!(BigBcd::from(5).rem_euclid(&BigBcd::from(0)) == Err(DivisionByZero));
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_by_zero_test() {
+        let _ = BigBcd::from(5) / BigBcd::from(0);
+    }
+
+    #[test]
+    fn root_test() {
+        use crate::big_bcd::RootError;
+        for n in 0u128..2000 {
+            let (s, r) = big(&n.to_string()).isqrt().unwrap();
+            let s_ = (n as f64).sqrt() as u128;
+            assert!(s == big(&s_.to_string()));
+            assert!(r == big(&(n - s_ * s_).to_string()));
+            assert!(big(&n.to_string()).is_perfect_square() == (s_ * s_ == n));
+        }
+
+        let mut state = 31;
+        for &len in [1, 5, 17, 18, 19, 40, 100, 333, 1000].iter() {
+            let n = big(&format!("1{}", random_digits(&mut state, len)));
+            for &k in [2, 3, 4, 5, 7, 12, 61].iter() {
+                let (s, r) = n.iroot(k).unwrap();
+                assert!(!r.is_negative());
+                assert!(s.pow(k).add(&r) == n);
+                assert!(s.add(&BigBcd::from(1)).pow(k) > n);
+                assert!(s.pow(k).iroot(k).unwrap() == (s.clone(), BigBcd::from(0)));
+            }
+        }
+        let (s, r) = big("-30").iroot(3).unwrap();
+        assert!(s == big("-3") && r == big("-3"));
+        assert!(big("-4").isqrt() == Err(RootError::EvenRootOfNegative));
+        assert!(BigBcd::from(4).iroot(0) == Err(RootError::ZeroDegree));
+        assert!(BigBcd::from(1000).iroot(5000).unwrap().0 == BigBcd::from(1));
+
+        let p = |s: &str, e| Some((big(s), e));
+        assert!(BigBcd::from(1usize << 60).is_perfect_power() == p("2", 60));
+        assert!(BigBcd::from(7776 * 7776).is_perfect_power() == p("6", 10));
+        assert!(big("1000000000000000000").is_perfect_power() == p("10", 18));
+        assert!(big("-64").is_perfect_power() == p("-4", 3));
+        assert!(BigBcd::from(12).is_perfect_power().is_none());
+        assert!(BigBcd::from(1).is_perfect_power().is_none());
+        assert!(BigBcd::from(15).pow(77).is_perfect_power() == p("15", 77));
+        assert!(BigBcd::from(15).pow(77).add(&BigBcd::from(1)).is_perfect_power().is_none());
+
+        assert!(BigBcd::from(2).sqrt_digits(10) == Ok(BigBcd::from(1414213562)));
+        assert!(BigBcd::from(1522756).sqrt_digits(3) == Ok(BigBcd::from(123)));
+        assert!(BigBcd::from(1522756).sqrt_digits(6) == Ok(BigBcd::from(123400)));
+        // Project Euler 80: the digital sum of the first 100 digits of sqrt(2).
+        let digits = BigBcd::from(2).sqrt_digits(100).unwrap().to_string();
+        assert!(digits.bytes().map(|d| (d - b'0') as u32).sum::<u32>() == 475);
+    }
+
+    #[test]
+    fn modular_test() {
+        assert!(BigBcd::from(3).pow(0) == BigBcd::from(1));
+        assert!(big("-3").pow(5) == big("-243"));
+
+        let mod_pow = |b: &str, e: &str, m: &str| {
+            big(b).mod_pow(&big(e), &big(m))
+        };
+        assert!(mod_pow("4", "13", "497") == Some(BigBcd::from(445)));
+        assert!(mod_pow("2", "0", "7") == Some(BigBcd::from(1)));
+        assert!(mod_pow("7", "1", "1") == Some(BigBcd::from(0)));
+        assert!(mod_pow("-2", "3", "-7") == Some(BigBcd::from(6)));
+        assert!(mod_pow("123456789", "987654321", "1000000007") == Some(BigBcd::from(652541198)));
+        let e = "10000000000000000000000000000000000000012345";
+        let res = mod_pow("98765432109876543210", e, "1000000000000000000000000000057");
+        assert!(res == Some(big("360238344035659770291525274298")));
+        // Fermat: a^(p - 1) == 1 mod p for the prime p = 2^127 - 1.
+        let p = big("170141183460469231731687303715884105727");
+        let a = big("-123456789123456789123456789");
+        assert!(a.mod_pow(&p.subtract(&BigBcd::from(1)), &p) == Some(BigBcd::from(1)));
+        assert!(a.mod_pow(&p, &p) == a.rem_euclid(&p).ok());
+        let inv = a.mod_inverse(&p).unwrap();
+        assert!(a.multiply(&inv).rem_euclid(&p) == Ok(BigBcd::from(1)));
+        assert!(a.mod_pow(&big("-1"), &p) == Some(inv.clone()));
+        assert!(a.mod_pow(&big("-3"), &p) == inv.mod_pow(&BigBcd::from(3), &p));
+        assert!(BigBcd::from(5).mod_pow(&BigBcd::from(3), &BigBcd::from(0)).is_none());
+        assert!(BigBcd::from(6).mod_pow(&big("-1"), &BigBcd::from(9)).is_none());
+
+        let mut state = 5;
+        for &len in [1, 3, 20, 60, 200].iter() {
+            let x = big(&format!("1{}", random_digits(&mut state, len)));
+            let y = big(&format!("1{}", random_digits(&mut state, len + 7)));
+            let z = big(&format!("1{}", random_digits(&mut state, len / 2)));
+            let (a, b) = (x.multiply(&z), y.multiply(&z).negated());
+            let g = a.gcd(&b);
+            assert!(g == a.gcd_binary(&b));
+            assert!(g.rem(&z) == Ok(BigBcd::from(0)));
+            assert!(a.divide(&g).unwrap().gcd(&b.divide(&g).unwrap()) == BigBcd::from(1));
+            let (g2, s, t) = a.extended_gcd(&b);
+            assert!(g2 == g && a.multiply(&s).add(&b.multiply(&t)) == g);
+            let l = a.lcm(&b);
+            assert!(l.multiply(&g) == a.multiply(&b).abs());
+            let e = big(&random_digits(&mut state, len));
+            let m = y.add(&BigBcd::from(2));
+            let expected = x.mod_pow(&e, &m).unwrap();
+            let e2 = e.add(&BigBcd::from(1));
+            assert!(x.mod_pow(&e2, &m) == Some(expected.multiply(&x).rem(&m).unwrap()));
+        }
+        assert!(BigBcd::from(0).gcd(&big("-12")) == BigBcd::from(12));
+        assert!(BigBcd::from(0).gcd_binary(&BigBcd::from(0)) == BigBcd::from(0));
+        assert!(BigBcd::from(48).gcd_binary(&BigBcd::from(1024)) == BigBcd::from(16));
+        assert!(BigBcd::from(0).lcm(&BigBcd::from(5)) == BigBcd::from(0));
+        assert!(BigBcd::from(4).mod_inverse(&BigBcd::from(8)).is_none());
+        assert!(BigBcd::from(3).mod_inverse(&BigBcd::from(1)) == Some(BigBcd::from(0)));
+    }
+
+    #[test]
+    fn mod_context_test() {
+        use crate::big_bcd::{ConstTimeModContext, ModContext};
+        let mut state = 8;
+        for &len in [1, 8, 9, 10, 30, 100, 400].iter() {
+            for &last in ["1", "3", "7", "0", "4", "5"].iter() {
+                let m = big(&format!("1{}{}", random_digits(&mut state, len), last));
+                let a = big(&random_digits(&mut state, len + 5)).negated();
+                let b = big(&random_digits(&mut state, len));
+                let e = big(&random_digits(&mut state, 30));
+                let reduce = |x: &BigBcd| x.rem_euclid(&m).unwrap();
+
+                let ctx = ModContext::new(&m).unwrap();
+                let (ra, rb) = (ctx.to_residue(&a), ctx.to_residue(&b));
+                assert!(ctx.from_residue(&ra) == reduce(&a));
+                assert!(ctx.from_residue(&ctx.mul_mod(&ra, &rb)) == reduce(&a.multiply(&b)));
+                assert!(ctx.from_residue(&ctx.sqr_mod(&ra)) == reduce(&a.multiply(&a)));
+                let p = ctx.from_residue(&ctx.pow_mod(&ra, &e));
+                let p1 = ctx.from_residue(&ctx.pow_mod(&ra, &e.add(&BigBcd::from(1))));
+                assert!(p1 == reduce(&p.multiply(&a)));
+                assert!(a.mod_pow(&e, &m) == Some(p.clone()));
+
+                let ct = ConstTimeModContext::new(&m);
+                if last == "0" || last == "4" || last == "5" {
+                    assert!(ct.is_none());
+                    continue;
+                }
+                let ct = ct.unwrap();
+                let (ra, rb) = (ct.to_residue(&a), ct.to_residue(&b));
+                assert!(ct.from_residue(&ra) == reduce(&a));
+                assert!(ct.from_residue(&ct.mul_mod(&ra, &rb)) == reduce(&a.multiply(&b)));
+                assert!(ct.from_residue(&ct.sqr_mod(&ra)) == reduce(&a.multiply(&a)));
+                assert!(ct.from_residue(&ct.pow_mod(&ra, &e)) == p);
+                let one = ct.pow_mod(&ra, &BigBcd::from(0));
+                assert!(ct.from_residue(&one) == reduce(&BigBcd::from(1)));
+            }
+        }
+        assert!(ModContext::new(&BigBcd::from(0)).is_err());
+        let ctx = ModContext::new(&big("-7")).unwrap();
+        assert!(*ctx.modulus() == BigBcd::from(7));
+        let three = ctx.to_residue(&BigBcd::from(3));
+        assert!(ctx.from_residue(&ctx.pow_mod(&three, &BigBcd::from(6))) == BigBcd::from(1));
+    }
+
+    #[test]
+    fn radix_test() {
+        let x = BigBcd::from(3).pow(200);
+        let hex = "1fd5863c3eb0469ec21a937a76f3432ffd73d97e447606b683ecf6f6e4a7ae225bfaff1eaaf8b0a1";
+        assert!(x.to_str_radix(16) == hex);
+        assert!(BigBcd::from_str_radix(hex, 16) == Ok(x.clone()));
+        assert!(format!("{:X}", x) == hex.to_uppercase());
+        assert!(x.to_str_radix(8).starts_with("37653030741753010647"));
+
+        for &v in [0i128, 1, -1, 255, -255, 1 << 40, i128::MAX, i128::MIN + 1].iter() {
+            let b = big(&v.to_string());
+            for radix in 2..=36 {
+                let s = b.to_str_radix(radix);
+                assert!(BigBcd::from_str_radix(&s, radix) == Ok(b.clone()));
+                assert!(i128::from_str_radix(&s, radix) == Ok(v));
+            }
+            let u = v.unsigned_abs();
+            assert!(format!("{:x}", b) == format!("{}{:x}", if v < 0 { "-" } else { "" }, u));
+            assert!(format!("{:#o}", b.abs()) == format!("{:#o}", u));
+            assert!(format!("{:#b}", b.abs()) == format!("{:#b}", u));
+            let be = b.to_bytes_be();
+            let trimmed: Vec<u8> = u.to_be_bytes().iter().skip_while(|&&x| x == 0).cloned().collect();
+            assert!(be == if u == 0 { vec![0] } else { trimmed });
+            assert!(BigBcd::from_bytes_be(&be) == b.abs());
+            assert!(BigBcd::from_bytes_le(&b.to_bytes_le()) == b.abs());
+        }
+        let s = format!("{:>8x}|{:<6b}|{:+#x}", BigBcd::from(255), BigBcd::from(5), BigBcd::from(16));
+        assert!(s == "      ff|101   |+0x10");
+        assert!(format!("{:010x}", big("-255")) == "-0000000ff");
+        assert!(BigBcd::from_bytes_be(&[]) == BigBcd::from(0));
+        assert!(BigBcd::from_bytes_be(&[0, 0, 1, 0]) == BigBcd::from(256));
+
+        let mut state = 77;
+        for &len in [10, 100, 500, 3000, 20000].iter() {
+            let x = big(&format!("-1{}", random_digits(&mut state, len)));
+            for &radix in [2, 3, 7, 16, 36].iter() {
+                assert!(BigBcd::from_str_radix(&x.to_str_radix(radix), radix) == Ok(x.clone()));
+            }
+            assert!(BigBcd::from_bytes_le(&x.to_bytes_le()) == x.abs());
+            let bytes = x.to_bytes_be();
+            assert!(bytes[0] != 0 && BigBcd::from_bytes_be(&bytes) == x.abs());
+        }
+    }
+
     #[test]
     fn ratio_new_test() {
         let a = Ratio::new(8usize, 4usize);
@@ -260,10 +1431,10 @@ mod tests {
 	let mut res2 = BigBcd::from(0);
 	let primes = segmented_sieve(0, 2000000);
 	for i in primes {
-	    res2 = res2.add(&BigBcd::from(i));
+	    res2 += i;
 	}
-	assert!(res == BigBcd::from("142913828922"));
-	assert!(res2 == BigBcd::from("142913828922"));
+	assert!(res == big("142913828922"));
+	assert!(res2 == big("142913828922"));
 
     }
 
@@ -336,3 +1507,4 @@ pub fn factors(n: usize) -> Vec<usize> {
     res.push(n);
     res
 }
+
@@ -1,36 +1,36 @@
 use num_traits::Num;
 use std::cmp::{Eq, PartialEq, PartialOrd, Ord, Ordering};
-use std::ops::{Add, Sub, Mul, Div, Rem};
+use std::ops::{Add, Sub, Mul, Div};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub struct Ratio<T: Num + Ord + Copy> {
+pub struct Ratio<T: Num + Ord + Clone> {
     pub numerator: T,
     pub denominator: T,
 }
 
-pub fn gcd<T: Num + Ord + Copy>(a: T, b: T) -> T {
+// Euclid's algorithm by remainders. Num already requires `%`, so this needs
+// no more of T than subtraction did, and takes O(log) steps where repeated
+// subtraction took up to max(a, b) / min(a, b).
+pub fn gcd<T: Num + Ord + Clone>(mut a: T, mut b: T) -> T {
     let z = T::zero();
 
-    if a == z {
-        b
-    } else if b == z || a == b {
-        a
-    } else if a > b {
-        gcd(a - b, b)
-    } else {
-        gcd(a, b - a)
+    while b != z {
+        let r = a % b.clone();
+        a = b;
+        b = r;
     }
+    a
 }
 
-fn abs_<T: Num + Ord + Copy>(x: T) -> T {
+fn abs_<T: Num + Ord + Clone>(x: T) -> T {
     if x < T::zero() {
-        x - (x + x)
+        T::zero() - x
     } else {
         x
     }
 }
 
-impl<T: Num + Ord + Copy> Ratio<T> {
+impl<T: Num + Ord + Clone> Ratio<T> {
     pub fn new(num: T, den: T) -> Ratio<T> {
         let mut res = Self {
             numerator: num,
@@ -41,18 +41,19 @@ impl<T: Num + Ord + Copy> Ratio<T> {
     }
 
     pub fn reduce(&mut self) {
-        let div: T = gcd(abs_(self.numerator), abs_(self.denominator));
-        self.numerator = self.numerator / div;
-        self.denominator = self.denominator / div;
+        let div: T = gcd(abs_(self.numerator.clone()), abs_(self.denominator.clone()));
+        self.numerator = self.numerator.clone() / div.clone();
+        self.denominator = self.denominator.clone() / div;
     }
 }
 
-impl<T: Num + Ord + Copy> Add for Ratio<T> {
+impl<T: Num + Ord + Clone> Add for Ratio<T> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
         let mut res = Ratio {
-            numerator: self.numerator * other.denominator + other.numerator * self.denominator,
+            numerator: self.numerator * other.denominator.clone()
+                + other.numerator * self.denominator.clone(),
             denominator: self.denominator * other.denominator,
         };
         res.reduce();
@@ -60,12 +61,13 @@ impl<T: Num + Ord + Copy> Add for Ratio<T> {
     }
 }
 
-impl<T: Num + Ord + Copy> Sub for Ratio<T> {
+impl<T: Num + Ord + Clone> Sub for Ratio<T> {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
         let mut res = Ratio {
-            numerator: self.numerator * other.denominator - other.numerator * self.denominator,
+            numerator: self.numerator * other.denominator.clone()
+                - other.numerator * self.denominator.clone(),
             denominator: self.denominator * other.denominator,
         };
         res.reduce();
@@ -73,7 +75,7 @@ impl<T: Num + Ord + Copy> Sub for Ratio<T> {
     }
 }
 
-impl<T: Num + Ord + Copy> Mul for Ratio<T> {
+impl<T: Num + Ord + Clone> Mul for Ratio<T> {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
@@ -86,7 +88,7 @@ impl<T: Num + Ord + Copy> Mul for Ratio<T> {
     }
 }
 
-impl<T: Num + Ord + Copy> Div for Ratio<T> {
+impl<T: Num + Ord + Clone> Div for Ratio<T> {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
@@ -99,15 +101,15 @@ impl<T: Num + Ord + Copy> Div for Ratio<T> {
     }
 }
 
-impl<T: Num + Ord + Copy> Ord for Ratio<T> {
+impl<T: Num + Ord + Clone> Ord for Ratio<T> {
     fn cmp(&self, other: &Self) -> Ordering {
-        let a = self.numerator * other.denominator;
-        let b = other.numerator * self.denominator;
+        let a = self.numerator.clone() * other.denominator.clone();
+        let b = other.numerator.clone() * self.denominator.clone();
         T::cmp(&a, &b)
     }
 }
 
-impl<T: Num + Ord + Copy> PartialOrd for Ratio<T> {
+impl<T: Num + Ord + Clone> PartialOrd for Ratio<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }